@@ -0,0 +1,174 @@
+use crate::viewer::{App, Format, Launcher, Mode, PopupMode, Query, SearchMode, Stats, View};
+
+use std::error::Error;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Run `dot-viewer` commands on the graph at `path`, without setting up a terminal.
+///
+/// Each command is what one would type after `:` in command mode, e.g. `neighbors 3`.
+/// A command starting with `/` applies a fuzzy search, one starting with `r/` applies a
+/// regex search, and one starting with `q/` applies a query search,
+/// moving the current node to the first match as in the TUI.
+/// Confirmations, e.g., to overwrite an exported file, are answered yes.
+/// Commands that only open a popup, e.g. `stats` or `jumps`, are rejected as errors.
+pub fn run(
+    path: String,
+    script: Option<String>,
+    exec: Option<String>,
+    export_dir: Option<PathBuf>,
+    viewer: Option<String>,
+) -> Result<(), Box<dyn Error>> {
+    let mut commands = Vec::new();
+
+    if let Some(script) = script {
+        let script = fs::read_to_string(&script)
+            .map_err(|e| format!("failed to read script {script:?}: {e}"))?;
+
+        let lines = script.lines().map(str::trim);
+        let lines = lines.filter(|line| !line.is_empty() && !line.starts_with('#'));
+        commands.extend(lines.map(String::from));
+    }
+
+    if let Some(exec) = exec {
+        let cmds = exec.split(';').map(str::trim).filter(|cmd| !cmd.is_empty());
+        commands.extend(cmds.map(String::from));
+    }
+
+    let mut app = App::new(&path)?;
+    app.launcher = Launcher::load(viewer)?;
+    if let Some(export_dir) = export_dir {
+        app.export_dir = export_dir;
+    }
+
+    for command in &commands {
+        let result = if let Some(key) = command.strip_prefix("r/") {
+            search(&mut app, SearchMode::Regex, key);
+            Ok(String::new())
+        } else if let Some(key) = command.strip_prefix("q/") {
+            search(&mut app, SearchMode::Query, key);
+            Ok(String::new())
+        } else if let Some(key) = command.strip_prefix('/') {
+            search(&mut app, SearchMode::Fuzzy, key);
+            Ok(String::new())
+        } else {
+            app.set_command_mode();
+            app.input.set(command.clone());
+            let mut result = app.exec();
+            match app.mode {
+                Mode::Popup(PopupMode::Confirm) if result.is_ok() => result = app.answer(true),
+                Mode::Popup(_) if result.is_ok() => {
+                    let err = format!("{command}: opens a popup, which needs the TUI");
+                    return Err(err.into());
+                }
+                _ => {}
+            }
+            result.map(|succ| succ.to_string())
+        };
+
+        match result {
+            Ok(msg) if !msg.is_empty() => println!("{msg}"),
+            Ok(_) => {}
+            Err(err) => return Err(format!("{command}: {err}").into()),
+        }
+    }
+
+    Ok(())
+}
+
+fn search(app: &mut App, smode: SearchMode, key: &str) {
+    app.set_search_mode(smode);
+    app.input.set(key.to_string());
+    app.update_search();
+    app.set_normal_mode();
+}
+
+/// Print statistics of the graph at `path`, as shown in the stats popup.
+pub fn stats(path: &str) -> Result<(), Box<dyn Error>> {
+    let mut app = App::new(path)?;
+    let view = app.tabs.selected();
+
+    let stats = Stats::new(&view.graph, &view.current.items, !view.cycles.items.is_empty())?;
+    for (text, _) in &stats.list.items {
+        println!("{text}");
+    }
+
+    Ok(())
+}
+
+/// Extract up to `depth` neighbors of `node` in the graph at `path`.
+pub fn neighbors(
+    path: &str,
+    node: &str,
+    depth: usize,
+    output: Option<String>,
+) -> Result<(), Box<dyn Error>> {
+    let mut app = App::new(path)?;
+    let view = app.tabs.selected();
+
+    view.goto(node)?;
+    let view = view.neighbors(depth)?;
+
+    write(&view, output)
+}
+
+/// Extract the subgraph `cluster` of the graph at `path`.
+pub fn subgraph(path: &str, cluster: &str, output: Option<String>) -> Result<(), Box<dyn Error>> {
+    let mut app = App::new(path)?;
+    let view = app.tabs.selected();
+
+    let view = view.subgraph_of(cluster)?;
+
+    write(&view, output)
+}
+
+/// Search nodes in the graph at `path`, printing the matching node ids,
+/// or writing the filtered graph if `output` is given.
+pub fn search(
+    path: &str,
+    key: &str,
+    regex: bool,
+    query: bool,
+    output: Option<String>,
+) -> Result<(), Box<dyn Error>> {
+    let mut app = App::new(path)?;
+    let view = app.tabs.selected();
+
+    if query {
+        // report an invalid query, instead of silently matching nothing
+        Query::new(key, &view.graph)?;
+        view.update_query(key);
+    } else if regex {
+        view.update_regex(key);
+    } else {
+        view.update_fuzzy(key);
+    }
+
+    if output.is_some() {
+        let view = view.filter()?;
+        return write(&view, output);
+    }
+
+    for (idx, _) in &view.matches.items {
+        println!("{}", view.current.items[*idx]);
+    }
+
+    Ok(())
+}
+
+fn write(view: &View, output: Option<String>) -> Result<(), Box<dyn Error>> {
+    match output {
+        Some(output) => {
+            let format = Format::from_path(Path::new(&output))?;
+            let mut file = fs::File::create(output)?;
+            format.write(&view.graph, &mut file)?;
+        }
+        None => {
+            let mut stdout = io::stdout().lock();
+            view.graph.to_dot(&mut stdout)?;
+        }
+    }
+
+    Ok(())
+}
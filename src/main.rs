@@ -1,29 +1,152 @@
+mod headless;
 mod terminal;
 mod ui;
 mod viewer;
 
+use std::env;
 use std::error::Error;
 use std::fs;
+use std::path::PathBuf;
 
 use chrono::prelude::*;
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use simplelog::{Config, LevelFilter, WriteLogger};
 
-use terminal::launch;
+use terminal::{launch, launch_diff, launch_session};
+use viewer::state_dir;
 
 #[derive(Parser, Default, Debug)]
+#[command(args_conflicts_with_subcommands = true)]
 struct Cli {
-    path: String,
+    /// Path to a dot file to open, which may be `.gz` or `.zst` compressed, or `-` for stdin
+    path: Option<String>,
+
+    /// Run commands from a script file, one per line, without opening the TUI
+    #[arg(long)]
+    script: Option<String>,
+
+    /// Run `;`-separated commands without opening the TUI
+    #[arg(long)]
+    exec: Option<String>,
+
+    /// Compare two dot files, viewing the union graph with changes highlighted
+    #[arg(
+        long,
+        num_args = 2,
+        value_names = ["BEFORE", "AFTER"],
+        conflicts_with_all = ["path", "script", "exec"]
+    )]
+    diff: Option<Vec<String>>,
+
+    /// Directory to save exported files in, `$XDG_DATA_HOME/dot-viewer/exports` by default
+    #[arg(long)]
+    export_dir: Option<PathBuf>,
+
+    /// Command to launch an external viewer with, `{file}` standing for the dot file,
+    /// e.g. `"xdot {file}"`, overriding `viewer` in `config.toml`
+    #[arg(long)]
+    viewer: Option<String>,
+
+    /// Restore tabs and marks from a session file saved by `:mksession`
+    #[arg(long, conflicts_with_all = ["path", "script", "exec", "diff"])]
+    session: Option<String>,
+
+    #[command(subcommand)]
+    query: Option<Query>,
+}
+
+/// One-shot queries on a graph, run without opening the TUI.
+#[derive(Subcommand, Debug)]
+enum Query {
+    /// Print node, edge, source and sink counts, and more statistics as in the stats popup
+    Stats { path: String },
+    /// Extract up to `depth` neighbors of a node
+    Neighbors {
+        path: String,
+        node: String,
+        depth: usize,
+        /// Write the extracted graph to a file in the format of its extension, e.g. `.dot` or `.json`,
+        /// instead of stdout
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+    /// Extract a subgraph (cluster)
+    Subgraph {
+        path: String,
+        cluster: String,
+        /// Write the extracted graph to a file in the format of its extension, e.g. `.dot` or `.json`,
+        /// instead of stdout
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+    /// Print node ids matching a fuzzy (or regex, or query) search
+    Search {
+        path: String,
+        key: String,
+        /// Match against the raw dot representation of nodes with a regex
+        #[arg(long, conflicts_with = "query")]
+        regex: bool,
+        /// Match nodes with a query on attributes and degrees, e.g. `shape == box and degree > 3`
+        #[arg(long)]
+        query: bool,
+        /// Write the graph filtered by the matches to a file in the format of its extension
+        #[arg(short, long)]
+        output: Option<String>,
+    },
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
     let args = Cli::parse();
 
-    fs::create_dir_all("./logs")?;
-    let file = fs::File::create(format!("logs/log_{}.log", Local::now()))?;
+    let logs = state_dir().unwrap_or_else(|| env::temp_dir().join("dot-viewer")).join("logs");
+    fs::create_dir_all(&logs)?;
+    let file = fs::File::create(logs.join(format!("log_{}.log", Local::now())))?;
     WriteLogger::init(LevelFilter::Info, Config::default(), file)?;
 
-    launch(args.path)?;
+    if let Some(query) = args.query {
+        let result = match query {
+            Query::Stats { path } => headless::stats(&path),
+            Query::Neighbors { path, node, depth, output } => {
+                headless::neighbors(&path, &node, depth, output)
+            }
+            Query::Subgraph { path, cluster, output } => {
+                headless::subgraph(&path, &cluster, output)
+            }
+            Query::Search { path, key, regex, query, output } => {
+                headless::search(&path, &key, regex, query, output)
+            }
+        };
+
+        exit_on_error(result);
+        return Ok(());
+    }
+
+    if let Some(diff) = args.diff {
+        let [before, after]: [String; 2] = diff.try_into().expect("clap takes exactly two values");
+        exit_on_error(launch_diff(before, after, args.export_dir, args.viewer));
+        return Ok(());
+    }
+
+    if let Some(session) = args.session {
+        exit_on_error(launch_session(session, args.export_dir, args.viewer));
+        return Ok(());
+    }
+
+    let path = args.path.ok_or("user should provide path to a dot file")?;
+
+    if args.script.is_some() || args.exec.is_some() {
+        exit_on_error(headless::run(path, args.script, args.exec, args.export_dir, args.viewer));
+        return Ok(());
+    }
+
+    exit_on_error(launch(path, args.export_dir, args.viewer));
 
     Ok(())
 }
+
+fn exit_on_error(result: Result<(), Box<dyn Error>>) {
+    if let Err(err) = result {
+        eprintln!("{err}");
+        std::process::exit(1);
+    }
+}
@@ -1,6 +1,11 @@
-use crate::{ui, viewer::App};
+use crate::{
+    ui,
+    viewer::{App, Keymap, Launcher},
+};
 
 use std::io::Stdout;
+use std::path::PathBuf;
+use std::time::Duration;
 use std::{error::Error, io};
 
 use crossterm::{
@@ -14,16 +19,58 @@ use tui::{
     Terminal,
 };
 
-pub fn launch(path: String) -> Result<(), Box<dyn Error>> {
+pub fn launch(
+    path: String,
+    export_dir: Option<PathBuf>,
+    viewer: Option<String>,
+) -> Result<(), Box<dyn Error>> {
+    let msg = "user should provide path to a valid dot file";
+    start(|| App::new(&path), msg, export_dir, viewer)
+}
+
+pub fn launch_diff(
+    before: String,
+    after: String,
+    export_dir: Option<PathBuf>,
+    viewer: Option<String>,
+) -> Result<(), Box<dyn Error>> {
+    let msg = "user should provide paths to valid dot files";
+    start(|| App::diff(&before, &after), msg, export_dir, viewer)
+}
+
+pub fn launch_session(
+    session: String,
+    export_dir: Option<PathBuf>,
+    viewer: Option<String>,
+) -> Result<(), Box<dyn Error>> {
+    let msg = "user should provide path to a valid session file";
+    start(|| App::session(&session), msg, export_dir, viewer)
+}
+
+fn start<E>(
+    new: impl FnOnce() -> Result<App, E>,
+    msg: &str,
+    export_dir: Option<PathBuf>,
+    viewer: Option<String>,
+) -> Result<(), Box<dyn Error>> {
+    // load configs before setting up terminal, so that errors are reported on the plain screen
+    let keymap = Keymap::load()?;
+    let launcher = Launcher::load(viewer)?;
+
     // setup terminal
     let mut terminal = setup()?;
 
     // create and run app
-    let app = App::new(&path).map_err(|_| {
+    let mut app = new().map_err(|_| {
         let _ = cleanup();
 
-        Box::<dyn Error>::from("user should provide path to a valid dot file")
+        Box::<dyn Error>::from(msg)
     })?;
+    app.keymap = keymap;
+    app.launcher = launcher;
+    if let Some(export_dir) = export_dir {
+        app.export_dir = export_dir;
+    }
     let _ = run(&mut terminal, app);
 
     // restore terminal
@@ -60,10 +107,17 @@ fn run<B: Backend>(terminal: &mut Terminal<B>, mut app: App) -> io::Result<()> {
     loop {
         terminal.draw(|f| ui::draw_app(f, &mut app))?;
 
-        if let Event::Key(key) = event::read()? {
-            app.key(key);
+        // poll with a timeout, so that changes to the dot file are picked up while idle
+        if event::poll(Duration::from_millis(500))? {
+            match event::read()? {
+                Event::Key(key) => app.key(key),
+                Event::Mouse(mouse) => app.mouse(mouse),
+                _ => {}
+            }
         }
 
+        app.tick();
+
         if app.quit {
             break;
         }
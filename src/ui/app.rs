@@ -1,5 +1,5 @@
 use crate::ui::{input::draw_input, popup::draw_popup, tabs::draw_tabs};
-use crate::viewer::{App, Mode};
+use crate::viewer::{App, Mode, PopupMode};
 
 use tui::{
     backend::Backend,
@@ -21,6 +21,11 @@ pub(crate) fn draw_app<B: Backend>(f: &mut Frame<B>, app: &mut App) {
 
     match &app.mode {
         Mode::Normal | Mode::Command | Mode::Search(_) => draw_main(f, size, app),
+        // ask for confirmation over the main screen
+        Mode::Popup(PopupMode::Confirm) => {
+            draw_main(f, size, app);
+            draw_popup(f, size, app);
+        }
         Mode::Popup(_) => draw_popup(f, size, app),
     }
 }
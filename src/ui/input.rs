@@ -16,6 +16,7 @@ pub(super) fn draw_input<B: Backend>(f: &mut Frame<B>, chunk: Rect, app: &mut Ap
         Mode::Search(smode) => match smode {
             SearchMode::Fuzzy => "Fuzzy Search",
             SearchMode::Regex => "Regex Search",
+            SearchMode::Query => "Query Search",
         },
         _ => unreachable!(),
     };
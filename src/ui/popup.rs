@@ -3,9 +3,10 @@ use crate::viewer::{App, Mode, PopupMode};
 
 use tui::{
     backend::Backend,
-    layout::{Constraint, Rect},
+    layout::{Alignment, Constraint, Rect},
     style::{Color, Modifier, Style},
-    widgets::{Block, Borders, Cell, Row, Table},
+    text::Spans,
+    widgets::{Block, Borders, Cell, Clear, List, ListItem, ListState, Paragraph, Row, Table},
     Frame,
 };
 use tui_tree_widget::Tree as TUITree;
@@ -16,6 +17,12 @@ pub(super) fn draw_popup<B: Backend>(f: &mut Frame<B>, size: Rect, app: &mut App
     match &app.mode {
         Mode::Popup(pmode) => match pmode {
             PopupMode::Tree => draw_tree(f, popup, app),
+            PopupMode::Jumps => draw_jumps(f, popup, app),
+            PopupMode::Marks => draw_marks(f, popup, app),
+            PopupMode::Lineage => draw_lineage(f, popup, app),
+            PopupMode::Stats => draw_stats(f, popup, app),
+            PopupMode::Cycles => draw_cycles(f, popup, app),
+            PopupMode::Confirm => draw_confirm(f, size, app),
             PopupMode::Help => draw_help(f, popup, app),
         },
         _ => unreachable!(),
@@ -40,6 +47,128 @@ fn draw_tree<B: Backend>(f: &mut Frame<B>, chunk: Rect, app: &mut App) {
     f.render_widget(block, chunk);
 }
 
+fn draw_jumps<B: Backend>(f: &mut Frame<B>, chunk: Rect, app: &mut App) {
+    let view = app.tabs.selected();
+    let jumps = &mut view.jumps;
+
+    let idx = jumps.idx;
+    let list: Vec<ListItem> = (jumps.list.items.iter())
+        .enumerate()
+        .map(|(i, id)| {
+            let distance = if i < idx { idx - i } else { i - idx };
+            ListItem::new(format!("{distance:>4}  {id}"))
+        })
+        .collect();
+
+    draw_list(f, chunk, "Jumps".to_string(), list, &mut jumps.list.state);
+}
+
+fn draw_marks<B: Backend>(f: &mut Frame<B>, chunk: Rect, app: &mut App) {
+    let marks = &mut app.marks;
+
+    let list: Vec<ListItem> = (marks.list.items.iter())
+        .map(|(name, id)| ListItem::new(format!("{name}  {id}")))
+        .collect();
+
+    draw_list(f, chunk, "Marks".to_string(), list, &mut marks.list.state);
+}
+
+fn draw_lineage<B: Backend>(f: &mut Frame<B>, chunk: Rect, app: &mut App) {
+    let tabs = &app.tabs;
+    let lineage = &mut app.lineage;
+
+    let current = tabs.tabs[tabs.state].id;
+    let list: Vec<ListItem> = (lineage.items.iter())
+        .map(|&(id, depth)| {
+            let indent = "    ".repeat(depth);
+            let branch = if depth == 0 { "" } else { "└── " };
+            let title = (tabs.tabs.iter().find(|view| view.id == id))
+                .map_or("(closed)", |view| view.title.as_str());
+            let item = ListItem::new(format!("{indent}{branch}{title}"));
+            if id == current {
+                item.style(Style::default().fg(Color::Yellow))
+            } else {
+                item
+            }
+        })
+        .collect();
+
+    draw_list(f, chunk, "Lineage".to_string(), list, &mut lineage.state);
+}
+
+fn draw_stats<B: Backend>(f: &mut Frame<B>, chunk: Rect, app: &mut App) {
+    let title = format!("Stats of {}", app.tabs.selected().title);
+    let stats = &mut app.stats;
+
+    let list: Vec<ListItem> = (stats.list.items.iter())
+        .map(|(text, id)| {
+            let item = ListItem::new(text.as_str());
+            if id.is_some() {
+                item
+            } else {
+                item.style(Style::default().add_modifier(Modifier::BOLD))
+            }
+        })
+        .collect();
+
+    draw_list(f, chunk, title, list, &mut stats.list.state);
+}
+
+fn draw_cycles<B: Backend>(f: &mut Frame<B>, chunk: Rect, app: &mut App) {
+    let view = app.tabs.selected();
+    let title = format!("Cycles of {}", view.title);
+    let cycles = &mut view.cycles;
+
+    let list: Vec<ListItem> = (cycles.items.iter())
+        .map(|cycle| {
+            let len = cycle.nodes.len();
+            let path = cycle.path.join(" -> ");
+            ListItem::new(format!("{len:>6} nodes  {path}"))
+        })
+        .collect();
+
+    draw_list(f, chunk, title, list, &mut cycles.state);
+}
+
+/// Draw `items` as a selectable list titled `title`, as the list popups do.
+fn draw_list<B: Backend>(
+    f: &mut Frame<B>,
+    chunk: Rect,
+    title: String,
+    items: Vec<ListItem>,
+    state: &mut ListState,
+) {
+    let block = surrounding_block(title, false);
+
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(
+            Style::default().fg(Color::Black).bg(Color::LightGreen).add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol(">> ");
+
+    f.render_stateful_widget(list, chunk, state);
+}
+
+fn draw_confirm<B: Backend>(f: &mut Frame<B>, size: Rect, app: &mut App) {
+    let Some(confirm) = &app.confirm else {
+        return;
+    };
+
+    let chunk = centered_rect(50, 20, size);
+    let block = surrounding_block("Confirm".to_string(), true);
+
+    let text = vec![
+        Spans::from(confirm.prompt()),
+        Spans::from(""),
+        Spans::from("y: yes, n: no, esc: cancel"),
+    ];
+    let paragraph = Paragraph::new(text).block(block).alignment(Alignment::Center);
+
+    f.render_widget(Clear, chunk);
+    f.render_widget(paragraph, chunk);
+}
+
 fn draw_help<B: Backend>(f: &mut Frame<B>, chunk: Rect, app: &mut App) {
     let header = app.help.header.iter().map(|s| {
         Cell::from(s.as_str()).style(Style::default().fg(Color::Green).add_modifier(Modifier::BOLD))
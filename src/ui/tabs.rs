@@ -20,11 +20,12 @@ pub(super) fn draw_tabs<B: Backend>(f: &mut Frame<B>, chunk: Rect, app: &mut App
     draw_nav_bar(f, chunks[0], app);
 
     let view = app.tabs.selected();
-    draw_view(f, chunks[1], view);
+    draw_view(f, chunks[1], view, app.diff.as_ref());
 }
 
 fn draw_nav_bar<B: Backend>(f: &mut Frame<B>, chunk: Rect, app: &mut App) {
     let block = Block::default().borders(Borders::ALL).title("Views");
+    app.tabs.area = block.inner(chunk);
 
     let titles: Vec<String> = app.tabs.tabs.iter().map(|view| view.title.clone()).collect();
     let titles = (titles.iter())
@@ -29,3 +29,62 @@ fn parse_node(node: &Node, texts: &mut Vec<String>) {
         _ => {}
     }
 }
+
+/// Rows of the first table in `html`, each a list of cells holding their lines of text.
+/// A cell spanning multiple columns is followed by empty cells, to keep the columns aligned.
+pub fn parse_table(html: &str) -> Option<Vec<Vec<String>>> {
+    let dom = Dom::parse(html).ok()?;
+    let table = dom.children.iter().find_map(find_table)?;
+
+    let mut rows = Vec::new();
+    collect_rows(table, &mut rows);
+
+    (!rows.is_empty()).then_some(rows)
+}
+
+fn find_table(node: &Node) -> Option<&Element> {
+    match node {
+        Node::Element(element) if element.name.eq_ignore_ascii_case("table") => Some(element),
+        Node::Element(element) => element.children.iter().find_map(find_table),
+        _ => None,
+    }
+}
+
+fn collect_rows(element: &Element, rows: &mut Vec<Vec<String>>) {
+    for node in &element.children {
+        let Node::Element(child) = node else { continue };
+
+        if child.name.eq_ignore_ascii_case("tr") {
+            rows.push(collect_cells(child));
+        } else if !child.name.eq_ignore_ascii_case("table") {
+            collect_rows(child, rows);
+        }
+    }
+}
+
+fn collect_cells(row: &Element) -> Vec<String> {
+    let mut cells = Vec::new();
+
+    for node in &row.children {
+        let Node::Element(cell) = node else { continue };
+        if !(cell.name.eq_ignore_ascii_case("td") || cell.name.eq_ignore_ascii_case("th")) {
+            continue;
+        }
+
+        let mut texts = Vec::new();
+        parse_element(cell, &mut texts);
+
+        let lines = texts.iter().flat_map(|text| text.split("\\l")).map(str::trim);
+        let lines: Vec<&str> = lines.filter(|line| !line.is_empty()).collect();
+        cells.push(lines.join("\n"));
+
+        let span = (cell.attributes.iter())
+            .find(|(key, _)| key.eq_ignore_ascii_case("colspan"))
+            .and_then(|(_, value)| value.as_deref())
+            .and_then(|value| value.trim().parse::<usize>().ok())
+            .unwrap_or(1);
+        cells.extend((1..span).map(|_| String::new()));
+    }
+
+    cells
+}
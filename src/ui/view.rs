@@ -1,10 +1,9 @@
 use crate::{
     ui::{surrounding_block, utils::htmlparser},
-    viewer::{Focus, View},
+    viewer::{Attrs, AttrsMode, Change, Critical, Diff, Focus, View},
 };
 
 use std::collections::{HashMap, HashSet};
-use std::fmt::Write;
 
 use dot_graph::Node;
 
@@ -13,49 +12,55 @@ use tui::{
     backend::Backend,
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
-    text::{Span, Spans},
-    widgets::{Block, List, ListItem, Paragraph, Wrap},
+    text::{Span, Spans, Text},
+    widgets::{Block, Cell, List, ListItem, Paragraph, Row, Table, Wrap},
     Frame,
 };
 
-pub(super) fn draw_view<B: Backend>(f: &mut Frame<B>, chunk: Rect, view: &mut View) {
+pub(super) fn draw_view<B: Backend>(
+    f: &mut Frame<B>,
+    chunk: Rect,
+    view: &mut View,
+    diff: Option<&Diff>,
+) {
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([Constraint::Percentage(35), Constraint::Percentage(65)].as_ref())
         .split(chunk);
 
-    draw_left(f, chunks[0], view);
-    draw_right(f, chunks[1], view);
+    draw_left(f, chunks[0], view, diff);
+    draw_right(f, chunks[1], view, diff);
 }
 
-fn draw_left<B: Backend>(f: &mut Frame<B>, chunk: Rect, view: &mut View) {
+fn draw_left<B: Backend>(f: &mut Frame<B>, chunk: Rect, view: &mut View, diff: Option<&Diff>) {
     if view.matches.items.is_empty() {
-        draw_current(f, chunk, view);
+        draw_current(f, chunk, view, diff);
     } else {
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([Constraint::Percentage(99), Constraint::Percentage(1)].as_ref())
             .split(chunk);
 
-        draw_current(f, chunks[0], view);
+        draw_current(f, chunks[0], view, diff);
         draw_match(f, chunks[1], view);
     }
 }
 
-fn draw_right<B: Backend>(f: &mut Frame<B>, chunk: Rect, view: &mut View) {
+fn draw_right<B: Backend>(f: &mut Frame<B>, chunk: Rect, view: &mut View, diff: Option<&Diff>) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
         .split(chunk);
 
-    draw_adjacent(f, chunks[0], view);
-    draw_metadata(f, chunks[1], view);
+    draw_adjacent(f, chunks[0], view, diff);
+    draw_metadata(f, chunks[1], view, diff);
 }
 
-fn draw_current<B: Backend>(f: &mut Frame<B>, chunk: Rect, view: &mut View) {
+fn draw_current<B: Backend>(f: &mut Frame<B>, chunk: Rect, view: &mut View, diff: Option<&Diff>) {
     let progress = view.progress_current();
     let title = format!("Nodes {progress}");
     let block = surrounding_block(title, view.focus == Focus::Current);
+    view.current.place(block.inner(chunk));
 
     let froms: HashSet<&String> = HashSet::from_iter(&view.prevs.items);
     let tos: HashSet<&String> = HashSet::from_iter(&view.nexts.items);
@@ -75,12 +80,29 @@ fn draw_current<B: Backend>(f: &mut Frame<B>, chunk: Rect, view: &mut View) {
                 }
             }
 
+            let critical = view.critical.as_ref().is_some_and(|c| c.nodes.contains(id));
+            if critical {
+                spans.insert(0, Span::styled("◆ ", Style::default().fg(Color::LightYellow)));
+            }
+
+            if view.cyclic.contains(id) {
+                spans.insert(0, Span::styled("↻ ", Style::default().fg(Color::Magenta)));
+            }
+
+            let change = diff.and_then(|diff| diff.nodes.get(id));
+            if let Some(change) = change {
+                let (marker, style) = change_style(change);
+                spans.insert(0, Span::styled(marker, style));
+            }
+
             let mut item = ListItem::new(Spans(spans));
 
             if froms.contains(&id) {
                 item = item.style(Style::default().fg(Color::Rgb(255, 150, 150)));
             } else if tos.contains(&id) {
                 item = item.style(Style::default().fg(Color::Rgb(150, 150, 255)));
+            } else if let Some(change) = change {
+                item = item.style(change_style(change).1);
             }
 
             item
@@ -102,21 +124,23 @@ fn draw_match<B: Backend>(f: &mut Frame<B>, chunk: Rect, view: &mut View) {
     f.render_widget(block, chunk);
 }
 
-fn draw_adjacent<B: Backend>(f: &mut Frame<B>, chunk: Rect, view: &mut View) {
+fn draw_adjacent<B: Backend>(f: &mut Frame<B>, chunk: Rect, view: &mut View, diff: Option<&Diff>) {
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
         .split(chunk);
 
-    draw_prevs(f, chunks[0], view);
-    draw_nexts(f, chunks[1], view);
+    draw_prevs(f, chunks[0], view, diff);
+    draw_nexts(f, chunks[1], view, diff);
 }
 
-fn draw_prevs<B: Backend>(f: &mut Frame<B>, chunk: Rect, view: &mut View) {
+fn draw_prevs<B: Backend>(f: &mut Frame<B>, chunk: Rect, view: &mut View, diff: Option<&Diff>) {
     let block = surrounding_block("Prev Nodes".to_string(), view.focus == Focus::Prev);
+    view.prevs.place(block.inner(chunk));
 
+    let current = view.current_id();
     let list: Vec<ListItem> = (view.prevs.items.par_iter())
-        .map(|id| ListItem::new(vec![Spans::from(Span::raw(id.as_str()))]))
+        .map(|id| adjacent_item(id, diff.and_then(|diff| diff.edge(id, &current))))
         .collect();
 
     let list = List::new(list)
@@ -127,11 +151,13 @@ fn draw_prevs<B: Backend>(f: &mut Frame<B>, chunk: Rect, view: &mut View) {
     f.render_stateful_widget(list, chunk, &mut view.prevs.state);
 }
 
-fn draw_nexts<B: Backend>(f: &mut Frame<B>, chunk: Rect, view: &mut View) {
+fn draw_nexts<B: Backend>(f: &mut Frame<B>, chunk: Rect, view: &mut View, diff: Option<&Diff>) {
     let block = surrounding_block("Next Nodes".to_string(), view.focus == Focus::Next);
+    view.nexts.place(block.inner(chunk));
 
+    let current = view.current_id();
     let list: Vec<ListItem> = (view.nexts.items.par_iter())
-        .map(|id| ListItem::new(vec![Spans::from(Span::raw(id.as_str()))]))
+        .map(|id| adjacent_item(id, diff.and_then(|diff| diff.edge(&current, id))))
         .collect();
 
     let list = List::new(list)
@@ -142,45 +168,284 @@ fn draw_nexts<B: Backend>(f: &mut Frame<B>, chunk: Rect, view: &mut View) {
     f.render_stateful_widget(list, chunk, &mut view.nexts.state);
 }
 
-fn draw_metadata<B: Backend>(f: &mut Frame<B>, chunk: Rect, view: &mut View) {
-    let block = surrounding_block("Attrs".to_string(), false);
+/// Adjacent node `id`, marked with the changes of the parallel edges in between.
+fn adjacent_item<'a>(id: &'a str, changes: Option<&[Option<Change>]>) -> ListItem<'a> {
+    match changes {
+        Some(changes) => {
+            let (marker, style) = edges_style(changes);
+            let spans = vec![Span::styled(marker, style), Span::raw(id)];
+            ListItem::new(vec![Spans::from(spans)]).style(style)
+        }
+        None => ListItem::new(vec![Spans::from(Span::raw(id))]),
+    }
+}
+
+fn change_style(change: &Change) -> (&'static str, Style) {
+    match change {
+        Change::Added => ("+ ", Style::default().fg(Color::Green)),
+        Change::Removed => ("- ", Style::default().fg(Color::Red)),
+        Change::Changed { .. } => ("~ ", Style::default().fg(Color::Yellow)),
+    }
+}
+
+/// Marker of parallel edges, added or removed only if all of them were, and changed otherwise.
+fn edges_style(changes: &[Option<Change>]) -> (&'static str, Style) {
+    let all = |kind: &Change| changes.iter().all(|change| change.as_ref() == Some(kind));
+
+    if all(&Change::Added) {
+        change_style(&Change::Added)
+    } else if all(&Change::Removed) {
+        change_style(&Change::Removed)
+    } else {
+        ("~ ", Style::default().fg(Color::Yellow))
+    }
+}
+
+fn draw_metadata<B: Backend>(f: &mut Frame<B>, chunk: Rect, view: &mut View, diff: Option<&Diff>) {
+    if let Some((from, to)) = view.selected_edge() {
+        draw_edge_metadata(f, chunk, view, diff, &from, &to);
+        return;
+    }
+
+    let title = format!("Attrs ({})", view.attrs.name());
+    let block = surrounding_block(title, false);
+    let inner = block.inner(chunk);
+    f.render_widget(block, chunk);
 
     let id = view.current_id();
     let node = view.graph.search_node(&id).unwrap();
 
-    let paragraph = Paragraph::new(pretty_metadata(node)).block(block).wrap(Wrap { trim: true });
+    let mut lines = match &view.critical {
+        Some(critical) => pretty_critical(&id, critical),
+        None => Vec::new(),
+    };
+
+    match diff.and_then(|diff| diff.nodes.get(&id)) {
+        Some(Change::Changed { before, after }) => {
+            lines.append(&mut pretty_diff(&id, before, after).lines);
+            let paragraph = Paragraph::new(Text::from(lines)).wrap(Wrap { trim: true });
+            f.render_widget(paragraph, inner);
+            return;
+        }
+        Some(Change::Added) => lines.push(Spans::from("(added)")),
+        Some(Change::Removed) => lines.push(Spans::from("(removed)")),
+        None => {}
+    }
 
-    f.render_widget(paragraph, chunk);
+    let table = match view.attrs {
+        AttrsMode::Rendered => {
+            lines.push(Spans::from(format!("[{id}]")));
+            lines.push(Spans::default());
+
+            let table = node.attrs().get("label").and_then(|label| htmlparser::parse_table(label));
+            if table.is_none() {
+                lines.append(&mut pretty_label(node));
+            }
+            table
+        }
+        AttrsMode::List => {
+            lines.push(Spans::from(format!("[{id}]")));
+            lines.push(Spans::default());
+            lines.append(&mut pretty_attrs(node));
+            None
+        }
+        AttrsMode::Raw => {
+            lines.append(&mut pretty_raw(node));
+            None
+        }
+    };
+
+    // keep indentation of raw dot text
+    let wrap = Wrap { trim: view.attrs != AttrsMode::Raw };
+
+    match table {
+        Some(rows) => {
+            // leave room for the lines above the table as wrapped
+            let width = inner.width.max(1) as usize;
+            let height: usize = lines.iter().map(|line| line.width().max(1).div_ceil(width)).sum();
+
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(height as u16), Constraint::Min(0)].as_ref())
+                .split(inner);
+
+            f.render_widget(Paragraph::new(Text::from(lines)).wrap(wrap), chunks[0]);
+            draw_label_table(f, chunks[1], rows);
+        }
+        None => f.render_widget(Paragraph::new(Text::from(lines)).wrap(wrap), inner),
+    }
 }
 
-fn pretty_metadata(node: &Node) -> String {
-    let mut metadata = String::new();
+/// Attributes of the edges from `from` to `to`, listing parallel edges one by one.
+fn draw_edge_metadata<B: Backend>(
+    f: &mut Frame<B>,
+    chunk: Rect,
+    view: &View,
+    diff: Option<&Diff>,
+    from: &str,
+    to: &str,
+) {
+    let block = surrounding_block("Edge Attrs".to_string(), false);
+
+    let mut lines = vec![Spans::from(format!("[{from} -> {to}]")), Spans::default()];
 
-    let id = node.id();
-    writeln!(metadata, "[{id}]").unwrap();
-    writeln!(metadata).unwrap();
+    let edges = view.edges.get(&(from.to_string(), to.to_string()));
+    let changes = diff.and_then(|diff| diff.edge(from, to));
+    lines.append(&mut pretty_edges(edges.map_or(&[], Vec::as_slice), changes));
+
+    let text = Text::from(lines);
+
+    let paragraph = Paragraph::new(text).block(block).wrap(Wrap { trim: true });
+
+    f.render_widget(paragraph, chunk);
+}
 
-    let empty = String::new();
-    let attrs = node.attrs();
-    let attrs_label = attrs.get("label").unwrap_or(&empty);
-    let attrs_label = htmlparser::parse(attrs_label);
+/// HTML-like table label, with each cell sized to fit its lines.
+fn draw_label_table<B: Backend>(f: &mut Frame<B>, chunk: Rect, rows: Vec<Vec<String>>) {
+    let columns = rows.iter().map(Vec::len).max().unwrap_or_default();
 
-    if attrs.is_empty() {
-        for (key, value) in attrs {
-            writeln!(metadata, "{key} : {value}").unwrap();
+    let mut widths = vec![0; columns];
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            let len = cell.lines().map(|line| line.chars().count()).max().unwrap_or_default();
+            *width = (*width).max(len);
         }
+    }
+    let widths: Vec<Constraint> =
+        widths.into_iter().map(|width| Constraint::Length(width as u16)).collect();
+
+    let rows = rows.into_iter().map(|row| {
+        let height = row.iter().map(|cell| cell.lines().count()).max().unwrap_or_default();
+        Row::new(row.into_iter().map(Cell::from)).height(height.max(1) as u16)
+    });
+
+    let table = Table::new(rows).widths(&widths).column_spacing(2);
+
+    f.render_widget(table, chunk);
+}
+
+/// Total weight of the critical path, and the slack of the node `id`.
+fn pretty_critical(id: &String, critical: &Critical) -> Vec<Spans<'static>> {
+    let weight = critical.attr.as_deref().unwrap_or("1 per node");
+    let total = critical.total;
+    let len = critical.path.len();
+    let slack = critical.slack.get(id).copied().unwrap_or_default();
+
+    let style = Style::default().fg(Color::LightYellow);
+    let mut lines = vec![Spans::from(Span::styled(
+        format!("critical path: {total} over {len} nodes (weight: {weight})"),
+        style,
+    ))];
+    if critical.nodes.contains(id) {
+        lines.push(Spans::from(Span::styled(format!("slack: {slack} (on critical path)"), style)));
     } else {
-        for attr in attrs_label {
-            if attr.starts_with("Input") {
-                continue;
+        lines.push(Spans::from(format!("slack: {slack}")));
+    }
+    lines.push(Spans::default());
+
+    lines
+}
+
+/// Attributes of a changed node, with removed and added values marked.
+fn pretty_diff(id: &str, before: &Attrs, after: &Attrs) -> Text<'static> {
+    let mut lines = vec![Spans::from(format!("[{id}] (changed)")), Spans::default()];
+    lines.append(&mut pretty_changes(before, after));
+
+    Text::from(lines)
+}
+
+/// Attributes before and after, with removed and added values marked.
+fn pretty_changes(before: &Attrs, after: &Attrs) -> Vec<Spans<'static>> {
+    let mut lines = Vec::new();
+
+    let mut keys = Vec::from_iter(before.keys().chain(after.keys()));
+    keys.sort_unstable();
+    keys.dedup();
+
+    for key in keys {
+        match (before.get(key), after.get(key)) {
+            (Some(prev), Some(next)) if prev == next => {
+                lines.push(Spans::from(format!("  {key} : {next}")));
             }
+            (prev, next) => {
+                if let Some(prev) = prev {
+                    let style = Style::default().fg(Color::Red);
+                    lines.push(Spans::from(Span::styled(format!("- {key} : {prev}"), style)));
+                }
+                if let Some(next) = next {
+                    let style = Style::default().fg(Color::Green);
+                    lines.push(Spans::from(Span::styled(format!("+ {key} : {next}"), style)));
+                }
+            }
+        }
+    }
 
-            let vals = attr.split("\\l");
-            for val in vals {
-                writeln!(metadata, "{val}").unwrap();
+    lines
+}
+
+/// Label of the node as text, split on `\l`, or all attributes if it has no label.
+fn pretty_label(node: &Node) -> Vec<Spans<'static>> {
+    let Some(label) = node.attrs().get("label").filter(|label| !label.is_empty()) else {
+        return pretty_attrs(node);
+    };
+
+    let texts = htmlparser::parse(label);
+    let lines = texts.iter().flat_map(|text| text.split("\\l"));
+
+    lines.map(|line| Spans::from(line.to_string())).collect()
+}
+
+/// All attributes of the node, sorted by key.
+fn pretty_attrs(node: &Node) -> Vec<Spans<'static>> {
+    let mut attrs = Vec::from_iter(node.attrs());
+    attrs.sort_unstable();
+
+    attrs.into_iter().map(|(key, value)| Spans::from(format!("{key} : {value}"))).collect()
+}
+
+/// Attributes of parallel edges, each sorted by key, numbered if there are many.
+/// When comparing graphs, each edge is marked with its own change, if any.
+fn pretty_edges(edges: &[Attrs], changes: Option<&[Option<Change>]>) -> Vec<Spans<'static>> {
+    let mut lines = Vec::new();
+
+    for (idx, attrs) in edges.iter().enumerate() {
+        let change = changes.and_then(|changes| changes.get(idx)).and_then(Option::as_ref);
+
+        if idx > 0 {
+            lines.push(Spans::default());
+        }
+        if edges.len() > 1 || change.is_some() {
+            let mut spans = Vec::new();
+            if let Some(change) = change {
+                let (marker, style) = change_style(change);
+                spans.push(Span::styled(marker, style));
             }
+            let style = Style::default().add_modifier(Modifier::BOLD);
+            spans.push(Span::styled(format!("edge {}/{}", idx + 1, edges.len()), style));
+            lines.push(Spans::from(spans));
+        }
+
+        if let Some(Change::Changed { before, after }) = change {
+            lines.append(&mut pretty_changes(before, after));
+            continue;
+        }
+
+        if attrs.is_empty() {
+            lines.push(Spans::from("(no attributes)"));
         }
+
+        let mut attrs = Vec::from_iter(attrs);
+        attrs.sort_unstable();
+        lines.extend(attrs.into_iter().map(|(key, value)| Spans::from(format!("{key} : {value}"))));
     }
 
-    metadata
+    lines
+}
+
+/// Raw dot text of the node.
+fn pretty_raw(node: &Node) -> Vec<Spans<'static>> {
+    let mut buffer = Vec::new();
+    node.to_dot(0, &mut buffer).expect("to_dot should succeed");
+
+    String::from_utf8_lossy(&buffer).lines().map(|line| Spans::from(line.to_string())).collect()
 }
@@ -1,18 +1,31 @@
 use crate::viewer::{
     command::{Command, CommandTrie},
+    confirm::Confirm,
+    diff::Diff,
     error::{DotViewerError, DotViewerResult},
     help,
+    keymap::{Action, Keymap},
+    launcher::Launcher,
     modes::{Mode, PopupMode, SearchMode},
+    recipe::SetOp,
+    session::{Session, Tab},
     success::Success,
-    utils::{Input, List, Table, Tabs},
+    utils::{
+        data_dir, is_stdin, load_graph, render, render_format, state_dir, Direction, Format,
+        History, Input, List, Marks, Stats, Table, Tabs,
+    },
     view::View,
 };
 
+use std::collections::{HashMap, HashSet};
+use std::env;
 use std::fs;
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::SystemTime;
 
-use dot_graph::{parser, Graph};
-
-use crossterm::event::KeyCode;
+use dot_graph::Graph;
 
 /// `App` holds `dot-viewer` application states.
 ///
@@ -28,14 +41,53 @@ pub(crate) struct App {
     /// Result of the last command that was made
     pub result: DotViewerResult<Success>,
 
+    /// Path to the dot file being viewed
+    pub path: String,
+
+    /// Modification time of the dot file when it was last loaded
+    pub modified: Option<SystemTime>,
+
     /// Tabs to be shown in the main screen
     pub tabs: Tabs<View>,
 
     /// Input form to be shown in the main screen
     pub input: Input,
 
-    /// Most recent key event
-    pub lookback: Option<KeyCode>,
+    /// Input history of command and search modes
+    pub histories: HashMap<Mode, History>,
+
+    /// Key sequence typed so far, waiting to be completed into an action
+    pub pending: Vec<String>,
+
+    /// Action waiting for a character argument, e.g., the name of a mark
+    pub operator: Option<Action>,
+
+    /// Keybindings for each mode
+    pub keymap: Keymap,
+
+    /// Named marks on nodes
+    pub marks: Marks,
+
+    /// Statistics of the view, shown in the stats popup
+    pub stats: Stats,
+
+    /// Lineage of tabs, each with its id and depth in the derivation tree
+    pub lineage: List<(usize, usize)>,
+
+    /// Action waiting for confirmation in the confirm popup
+    pub confirm: Option<Confirm>,
+
+    /// Directory that exported files are saved in by default
+    pub export_dir: PathBuf,
+
+    /// External viewer that `:view` launches
+    pub launcher: Launcher,
+
+    /// Path to the dot file compared against, when comparing two graphs
+    pub before: Option<String>,
+
+    /// Changes between two graphs, when comparing them
+    pub diff: Option<Diff>,
 
     /// Autocomplete support for commands
     pub trie: CommandTrie,
@@ -47,26 +99,191 @@ pub(crate) struct App {
 impl App {
     /// Constructs a new `App`, given a `path` to a dot format DAG.
     pub fn new(path: &str) -> DotViewerResult<Self> {
+        Self::open(path, None)
+    }
+
+    /// Constructs a new `App` comparing two dot format DAGs, `before` and `after`,
+    /// viewing the union of both graphs.
+    ///
+    /// The `after` file is the one watched for changes, and that marks and sessions are kept for.
+    pub fn diff(before: &str, after: &str) -> DotViewerResult<Self> {
+        Self::open(after, Some(before))
+    }
+
+    fn open(path: &str, before: Option<&str>) -> DotViewerResult<Self> {
         let quit = false;
 
         let mode = Mode::Normal;
 
         let result: DotViewerResult<Success> = Ok(Success::default());
 
-        let graph = parser::parse(path)?;
+        let modified = modified(path);
+        let (graph, diff) = load(path, before)?;
 
-        let view = View::new(graph.id().clone(), graph)?;
+        let view = View::new(root_title(&graph, path, before), graph)?;
         let tabs = Tabs::from_iter(vec![view]);
 
         let input = Input::default();
 
-        let lookback = None;
+        let histories = [
+            (Mode::Command, "command"),
+            (Mode::Search(SearchMode::Fuzzy), "fuzzy"),
+            (Mode::Search(SearchMode::Regex), "regex"),
+            (Mode::Search(SearchMode::Query), "query"),
+        ];
+        let histories = (histories.into_iter())
+            .map(|(mode, name)| {
+                let path = state_dir().map(|dir| dir.join("history").join(name));
+                (mode, History::load(path))
+            })
+            .collect();
+
+        let pending = Vec::new();
+
+        let operator = None;
+
+        let keymap = Keymap::default();
+
+        let marks = Marks::load((!is_stdin(path)).then(|| PathBuf::from(format!("{path}.marks"))));
+
+        let stats = Stats::default();
+
+        let lineage = List::from_iter(Vec::new());
+
+        let confirm = None;
+
+        let export_dir = data_dir().map_or(PathBuf::from("exports"), |dir| dir.join("exports"));
+
+        let launcher = Launcher::default();
+
+        let before = before.map(String::from);
 
         let trie = CommandTrie::new();
 
         let help = Table::new(help::HEADER, help::ROWS);
 
-        Ok(Self { quit, mode, result, tabs, input, lookback, trie, help })
+        let path = path.to_string();
+
+        Ok(Self {
+            quit,
+            mode,
+            result,
+            path,
+            modified,
+            tabs,
+            input,
+            histories,
+            pending,
+            operator,
+            keymap,
+            marks,
+            stats,
+            lineage,
+            confirm,
+            export_dir,
+            launcher,
+            before,
+            diff,
+            trie,
+            help,
+        })
+    }
+
+    /// Constructs a new `App` restoring the session saved in the file at `session`,
+    /// rebuilding each tab by replaying how it was derived.
+    /// Tabs that cannot be derived from the dot file any more are dropped.
+    pub fn session(session: &str) -> DotViewerResult<Self> {
+        let session = Session::load(session)?;
+
+        let mut app = Self::open(&session.path, session.before.as_deref())?;
+
+        for (name, id) in session.marks {
+            app.marks.set(name, id);
+        }
+
+        let root = app.tabs.tabs.pop().expect("there is always the root tab");
+        let (views, state, dropped) = rebuild(root, session.tabs, session.selected);
+        app.tabs.tabs = views;
+        app.tabs.state = state;
+
+        app.result = Ok(Success::RestoreSuccess { dropped });
+
+        Ok(app)
+    }
+
+    /// Save the open tabs and marks as a session to the file at `filename`.
+    pub fn mksession(&mut self, filename: String) -> DotViewerResult<Success> {
+        self.set_normal_mode();
+
+        // stdin cannot be read again on restoring
+        if is_stdin(&self.path) {
+            return Err(DotViewerError::CommandError(
+                "cannot save a session for a graph read from stdin".to_string(),
+            ));
+        }
+
+        // the session may be restored from another working directory
+        let canonicalize = |path: &String| {
+            fs::canonicalize(path).map_or(path.clone(), |path| path.to_string_lossy().to_string())
+        };
+        let path = canonicalize(&self.path);
+        let before = self.before.as_ref().map(canonicalize);
+
+        let tabs = self.tabs.tabs.iter().map(|view| view.snapshot()).collect();
+        let marks = self.marks.list.items.clone();
+        let session = Session { path, before, tabs, selected: self.tabs.state, marks };
+        session.save(&filename)?;
+
+        Ok(Success::SessionSuccess(filename))
+    }
+
+    /// Reload the graph if the dot file was modified since it was last loaded.
+    pub fn tick(&mut self) {
+        let modified = modified(&self.path);
+
+        if modified.is_some() && modified != self.modified {
+            self.result = self.reload();
+
+            // retry on the next tick if the file is still being written, and fails to parse
+            if self.result.is_ok() {
+                self.modified = modified;
+            }
+        }
+    }
+
+    /// Re-parse the dot file and rebuild each tab by replaying how it was derived.
+    /// Tabs that cannot be derived from the new graph any more are dropped.
+    pub fn reload(&mut self) -> DotViewerResult<Success> {
+        let (graph, diff) = load(&self.path, self.before.as_deref())?;
+        let title = root_title(&graph, &self.path, self.before.as_deref());
+        let root = View::new(title, graph)?;
+
+        let old_root = &self.tabs.tabs[0];
+        let ids: HashSet<&String> = root.current.items.iter().collect();
+        let vanished =
+            (old_root.current.items.iter()).filter(|id| !ids.contains(id)).cloned().collect();
+
+        let tabs = self.tabs.tabs.iter().map(View::snapshot).collect();
+        let (mut views, state, dropped) = rebuild(root, tabs, self.tabs.state);
+
+        let mut olds: HashMap<usize, View> =
+            mem::take(&mut self.tabs.tabs).into_iter().map(|view| (view.id, view)).collect();
+        for view in &mut views {
+            if let Some(old) = olds.remove(&view.id) {
+                view.restore(old);
+            }
+        }
+
+        self.tabs.tabs = views;
+        self.tabs.state = state;
+        self.diff = diff;
+
+        // the lineage popup may list tabs that were dropped
+        if self.mode == Mode::Popup(PopupMode::Lineage) {
+            self.update_lineage();
+        }
+
+        Ok(Success::ReloadSuccess { vanished, dropped })
     }
 
     /// Navigate to the next match.
@@ -83,16 +300,113 @@ impl App {
         view.goto_match()
     }
 
-    /// Navigate to the first.
-    pub fn goto_first(&mut self) -> DotViewerResult<()> {
-        if let Some(KeyCode::Char('g')) = self.lookback {
-            let view = self.tabs.selected();
-            view.goto_first()?;
+    /// Navigate to the next node that changed, in diff mode.
+    pub fn goto_next_change(&mut self) -> DotViewerResult<()> {
+        self.goto_change(true)
+    }
+
+    /// Navigate to the previous node that changed, in diff mode.
+    pub fn goto_prev_change(&mut self) -> DotViewerResult<()> {
+        self.goto_change(false)
+    }
+
+    fn goto_change(&mut self, forward: bool) -> DotViewerResult<()> {
+        let diff = (self.diff.as_ref())
+            .ok_or(DotViewerError::ViewerError("not comparing graphs".to_string()))?;
+
+        let view = self.tabs.selected();
+        let items = &view.current.items;
+        let len = items.len();
+        let idx = view.current.state.selected().unwrap_or(0);
+
+        let idx = (1..=len)
+            .map(|offset| if forward { (idx + offset) % len } else { (idx + len - offset) % len })
+            .find(|&idx| diff.touches(&items[idx]))
+            .ok_or(DotViewerError::ViewerError("no changes in the view".to_string()))?;
+
+        let id = items[idx].clone();
+        view.goto(&id)
+    }
+
+    /// Navigate back in the jump list.
+    pub fn jump_back(&mut self) -> DotViewerResult<()> {
+        let view = self.tabs.selected();
+        view.jump_back()
+    }
+
+    /// Navigate forward in the jump list.
+    pub fn jump_forward(&mut self) -> DotViewerResult<()> {
+        let view = self.tabs.selected();
+        view.jump_forward()
+    }
+
+    /// Navigate to the jump selected in the jump list popup.
+    pub fn jump_selected(&mut self) -> DotViewerResult<()> {
+        self.set_normal_mode();
+
+        let view = self.tabs.selected();
+        view.jump_selected()
+    }
+
+    /// Mark the currently selected node with `name`.
+    pub fn set_mark(&mut self, name: char) -> DotViewerResult<()> {
+        if !name.is_ascii_lowercase() {
+            return Err(DotViewerError::ViewerError(format!("invalid mark {name:?}")));
         }
 
+        let view = self.tabs.selected();
+        self.marks.set(name, view.current_id());
+
         Ok(())
     }
 
+    /// Navigate to the node marked with `name`,
+    /// moving to another tab if the current tab does not contain it.
+    pub fn goto_mark(&mut self, name: char) -> DotViewerResult<()> {
+        let id = (self.marks)
+            .get(name)
+            .ok_or(DotViewerError::ViewerError(format!("no such mark {name:?}")))?;
+
+        if self.tabs.selected().current.find(id.clone()).is_none() {
+            let idx = (self.tabs.tabs.iter())
+                .position(|view| view.current.find(id.clone()).is_some())
+                .ok_or(DotViewerError::ViewerError(format!("no tab contains node {id:?}")))?;
+            self.tabs.select(idx);
+        }
+
+        let view = self.tabs.selected();
+        view.goto(&id)
+    }
+
+    /// Navigate to the node marked with the mark selected in the marks popup.
+    pub fn goto_mark_selected(&mut self) -> DotViewerResult<()> {
+        self.set_normal_mode();
+
+        let (name, _) = (self.marks.list)
+            .selected()
+            .ok_or(DotViewerError::ViewerError("no mark selected".to_string()))?;
+
+        self.goto_mark(name)
+    }
+
+    /// Navigate to the node in the row selected in the stats popup.
+    pub fn goto_stat_selected(&mut self) -> DotViewerResult<()> {
+        self.set_normal_mode();
+
+        let id = (self.stats)
+            .selected()
+            .ok_or(DotViewerError::ViewerError("no node in the selected row".to_string()))?;
+
+        let view = self.tabs.selected();
+        view.goto(&id)
+    }
+
+    /// Navigate to the first.
+    pub fn goto_first(&mut self) -> DotViewerResult<()> {
+        let view = self.tabs.selected();
+        view.goto_first()
+    }
+
     /// Navigate to the last.
     pub fn goto_last(&mut self) -> DotViewerResult<()> {
         let view = self.tabs.selected();
@@ -106,14 +420,13 @@ impl App {
                 let view = self.tabs.selected();
                 let key = &self.input.key;
 
-                match smode {
-                    SearchMode::Fuzzy => view.update_fuzzy(key),
-                    SearchMode::Regex => view.update_regex(key),
-                }
-                view.update_trie();
+                view.update_search(smode, key);
 
-                // ignore goto errors while updating search matches
-                let _ = view.goto_match();
+                // ignore goto errors while updating search matches,
+                // and do not record jumps until the search is applied
+                if let Some(id) = view.matched_id() {
+                    let _ = view.select(&id);
+                }
             }
             _ => unreachable!(),
         }
@@ -159,6 +472,46 @@ impl App {
         }
     }
 
+    /// Recall the previous entry from the history of the current mode.
+    pub fn recall_prev(&mut self) {
+        let entry = (self.histories.get_mut(&self.mode))
+            .and_then(|history| history.previous(&self.input.key));
+        self.recall(entry);
+    }
+
+    /// Recall the next entry from the history of the current mode.
+    pub fn recall_next(&mut self) {
+        let entry = self.histories.get_mut(&self.mode).and_then(|history| history.next());
+        self.recall(entry);
+    }
+
+    /// Search backwards in the history of the current mode for an entry containing the input.
+    pub fn recall_search(&mut self) -> DotViewerResult<()> {
+        let entry = (self.histories.get_mut(&self.mode))
+            .and_then(|history| history.search(&self.input.key))
+            .ok_or(DotViewerError::ViewerError("no match in history".to_string()))?;
+        self.recall(Some(entry));
+
+        Ok(())
+    }
+
+    fn recall(&mut self, entry: Option<String>) {
+        if let Some(entry) = entry {
+            self.input.set(entry);
+
+            if let Mode::Search(_) = &self.mode {
+                self.update_search();
+            }
+        }
+    }
+
+    /// Save the input to the history of the current mode.
+    pub fn save_history(&mut self) {
+        if let Some(history) = self.histories.get_mut(&self.mode) {
+            history.push(self.input.key.clone());
+        }
+    }
+
     /// Parse and execute dot-viewer command
     pub fn exec(&mut self) -> DotViewerResult<Success> {
         let command = Command::parse(&self.input.key);
@@ -168,9 +521,28 @@ impl App {
                 Err(DotViewerError::CommandError("No argument supplied for neighbors".to_string())),
                 |depth| self.neighbors(depth).map(|_| Success::default()),
             ),
+            Command::Cone(cone) => {
+                self.cone(cone.direction, cone.depth, cone.matches).map(|_| Success::default())
+            }
+            Command::Path(path) => path.to.map_or(
+                Err(DotViewerError::CommandError("No argument supplied for path".to_string())),
+                |to| self.path(path.from, to, path.shortest).map(|_| Success::default()),
+            ),
             Command::Export(export) => self.export(export.filename),
-            Command::Xdot(xdot) => self.xdot(xdot.filename),
+            Command::Render(render) => self.render(render.filename, render.engine),
+            Command::Mksession(mksession) => mksession.filename.map_or(
+                Err(DotViewerError::CommandError("No argument supplied for mksession".to_string())),
+                |filename| self.mksession(filename),
+            ),
+            Command::View(view) => self.view(view.filename),
             Command::Filter => self.filter().map(|_| Success::default()),
+            Command::Combine(combine) => combine.tab.map_or(
+                Err(DotViewerError::CommandError(format!(
+                    "No argument supplied for {}",
+                    combine.op.name()
+                ))),
+                |tab| self.combine(combine.op, &tab).map(|_| Success::default()),
+            ),
             Command::Help => {
                 self.set_popup_mode(PopupMode::Help);
                 Ok(Success::default())
@@ -179,6 +551,43 @@ impl App {
                 self.set_popup_mode(PopupMode::Tree);
                 Ok(Success::default())
             }
+            Command::Jumps => {
+                let jumps = &mut self.tabs.selected().jumps;
+                let idx = jumps.idx.min(jumps.list.items.len().saturating_sub(1));
+                jumps.list.select(idx);
+
+                self.set_popup_mode(PopupMode::Jumps);
+                Ok(Success::default())
+            }
+            Command::Marks => {
+                self.set_popup_mode(PopupMode::Marks);
+                Ok(Success::default())
+            }
+            Command::Lineage => {
+                self.update_lineage();
+
+                self.set_popup_mode(PopupMode::Lineage);
+                Ok(Success::default())
+            }
+            Command::Stats => {
+                let view = self.tabs.selected();
+                self.stats =
+                    Stats::new(&view.graph, &view.current.items, !view.cycles.items.is_empty())?;
+
+                self.set_popup_mode(PopupMode::Stats);
+                Ok(Success::default())
+            }
+            Command::Cycles => {
+                let cycles = &mut self.tabs.selected().cycles;
+                if cycles.items.is_empty() {
+                    return Err(DotViewerError::ViewerError("no cycles in the view".to_string()));
+                }
+                cycles.first();
+
+                self.set_popup_mode(PopupMode::Cycles);
+                Ok(Success::default())
+            }
+            Command::Critical(critical) => self.critical(critical.attr).map(|_| Success::default()),
             Command::NoMatch => {
                 self.set_normal_mode();
 
@@ -196,42 +605,192 @@ impl App {
 
         let view_current = self.tabs.selected();
         let view_new = view_current.neighbors(depth)?;
-        self.tabs.open(view_new);
+        self.open(view_new);
 
         Ok(())
     }
 
-    /// Export the current view to dot.
+    /// Extract a subgraph of the ancestors (or descendants) of the currently selected node,
+    /// or of all matched nodes if `matches` is set, up to `depth` hops if given.
+    /// It opens a new tab with the cone graph view.
+    pub fn cone(
+        &mut self,
+        direction: Direction,
+        depth: Option<usize>,
+        matches: bool,
+    ) -> DotViewerResult<()> {
+        self.set_normal_mode();
+
+        let view_current = self.tabs.selected();
+        let seeds = if matches {
+            let items = &view_current.current.items;
+            (view_current.matches.items.iter()).map(|(idx, _)| items[*idx].clone()).collect()
+        } else {
+            vec![view_current.current_id()]
+        };
+
+        if seeds.is_empty() {
+            return Err(DotViewerError::ViewerError("no matches to start from".to_string()));
+        }
+
+        let view_new = view_current.cone(&seeds, depth, direction)?;
+        self.open(view_new);
+
+        Ok(())
+    }
+
+    /// Extract a subgraph of nodes on paths between `from` and `to`,
+    /// starting from the currently selected node if `from` is not given.
+    /// It opens a new tab with the path graph view.
+    pub fn path(
+        &mut self,
+        from: Option<String>,
+        to: String,
+        shortest: bool,
+    ) -> DotViewerResult<()> {
+        self.set_normal_mode();
+
+        let view_current = self.tabs.selected();
+        let from = from.unwrap_or_else(|| view_current.current_id());
+        let view_new = view_current.path(&from, &to, shortest)?;
+        self.open(view_new);
+
+        Ok(())
+    }
+
+    /// Export the current view to `filename`, or to a file named after the view by default.
+    /// A bare filename is placed in the export directory, while other paths are taken as is.
+    /// Asks before overwriting an existing file.
     pub fn export(&mut self, filename: Option<String>) -> DotViewerResult<Success> {
         self.set_normal_mode();
 
-        let viewer = self.tabs.selected();
-        let graph = &viewer.graph;
+        let filename = filename.unwrap_or_else(|| format!("{}.dot", self.default_filename()));
+
+        let path = self.export_path(&filename);
+        Format::from_path(&path)?;
 
-        let default: String = viewer.title.chars().filter(|c| !c.is_whitespace()).collect();
-        let filename = filename.unwrap_or(format!("{default}.dot"));
+        if path.exists() {
+            self.confirm = Some(Confirm::Overwrite { path });
+            self.set_popup_mode(PopupMode::Confirm);
 
-        write_graph(filename, graph)
+            return Ok(Success::default());
+        }
+
+        self.write_graph(&path)
     }
 
-    /// Launch `xdot.py`.
-    pub fn xdot(&mut self, filename: Option<String>) -> DotViewerResult<Success> {
+    /// Render the current view to `filename` with the Graphviz layout `engine`, `dot` by default,
+    /// or to an svg file named after the view.
+    /// Asks before overwriting an existing file.
+    pub fn render(
+        &mut self,
+        filename: Option<String>,
+        engine: Option<String>,
+    ) -> DotViewerResult<Success> {
         self.set_normal_mode();
 
-        let filename = filename.unwrap_or_else(|| "current.dot".to_string());
-        let path = format!("./exports/{filename}");
+        let filename = filename.unwrap_or_else(|| format!("{}.svg", self.default_filename()));
+        let engine = engine.unwrap_or_else(|| "dot".to_string());
+
+        let path = self.export_path(&filename);
+        render_format(&path)?;
+
+        if path.exists() {
+            self.confirm = Some(Confirm::Render { path, engine });
+            self.set_popup_mode(PopupMode::Confirm);
+
+            return Ok(Success::default());
+        }
+
+        self.render_graph(&path, &engine)
+    }
+
+    fn render_graph(&mut self, path: &Path, engine: &str) -> DotViewerResult<Success> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+
+        let view = self.tabs.selected();
+        render(&view.graph, engine, path)?;
+
+        Ok(Success::RenderSuccess(path.to_string_lossy().to_string()))
+    }
+
+    /// Name of the current view usable as a filename.
+    fn default_filename(&mut self) -> String {
+        let view = self.tabs.selected();
+
+        (view.title.chars())
+            .filter(|c| !c.is_whitespace())
+            .map(|c| if c == '/' { '_' } else { c })
+            .collect()
+    }
+
+    /// Resolve `filename` to export to, expanding `~` to the home directory.
+    fn export_path(&self, filename: &str) -> PathBuf {
+        let path = match (filename.strip_prefix('~'), env::var_os("HOME")) {
+            (Some(rest), Some(home)) if rest.is_empty() || rest.starts_with('/') => {
+                PathBuf::from(home).join(rest.trim_start_matches('/'))
+            }
+            _ => PathBuf::from(filename),
+        };
+
+        let bare = path.parent().is_none_or(|parent| parent.as_os_str().is_empty());
+        if bare {
+            self.export_dir.join(path)
+        } else {
+            path
+        }
+    }
+
+    /// Write the current view to `path`, in the format of its extension.
+    fn write_graph(&mut self, path: &Path) -> DotViewerResult<Success> {
+        let filename = path.to_string_lossy().to_string();
+        let format = Format::from_path(path)?;
+
+        let graph = &self.tabs.selected().graph;
+
+        let mut open_options = fs::OpenOptions::new();
+        let open_options = open_options.write(true).truncate(true).create(true);
 
-        if !std::path::Path::new("./exports/current.dot").exists() {
-            return Err(DotViewerError::XdotError);
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
         }
 
-        let xdot = std::process::Command::new("xdot")
-            .stdout(std::process::Stdio::null())
-            .stderr(std::process::Stdio::null())
-            .arg(&path)
-            .spawn();
+        let mut file_export = open_options.open(path)?;
+        format.write(graph, &mut file_export)?;
+
+        Ok(Success::ExportSuccess(filename))
+    }
+
+    /// Launch the external viewer on `filename`, resolved as in `export`,
+    /// or on the current view written to a temporary file by default.
+    pub fn view(&mut self, filename: Option<String>) -> DotViewerResult<Success> {
+        self.set_normal_mode();
+
+        let path = match filename {
+            Some(filename) => {
+                let path = self.export_path(&filename);
+                if !path.exists() {
+                    return Err(DotViewerError::CommandError(format!(
+                        "no such file: {}",
+                        path.display()
+                    )));
+                }
+                path
+            }
+            None => {
+                let title = self.tabs.selected().title.clone();
+                let path = self.launcher.temp(&title);
+
+                let mut file = fs::File::create(&path)?;
+                self.tabs.selected().graph.to_dot(&mut file)?;
 
-        xdot.map(|_| Success::XdotSuccess).map_err(|_| DotViewerError::XdotError)
+                path
+            }
+        };
+
+        self.launcher.launch(&path).map(Success::LaunchSuccess)
     }
 
     /// Apply filter on the current view, based on the current matches.
@@ -241,7 +800,30 @@ impl App {
 
         let view_current = self.tabs.selected();
         let view_new = view_current.filter()?;
-        self.tabs.open(view_new);
+        self.open(view_new);
+
+        Ok(())
+    }
+
+    /// Combine the current view with the tab `tab`, given by its title or its index from 1,
+    /// by the set operation `op`.
+    /// Opens a new tab with the combined view.
+    pub fn combine(&mut self, op: SetOp, tab: &str) -> DotViewerResult<()> {
+        self.set_normal_mode();
+
+        let tabs = &self.tabs.tabs;
+        let idx = (tabs.iter().position(|view| view.title == tab))
+            .or_else(|| {
+                tab.parse::<usize>()
+                    .ok()
+                    .filter(|idx| (1..=tabs.len()).contains(idx))
+                    .map(|idx| idx - 1)
+            })
+            .ok_or(DotViewerError::ViewerError(format!("no such tab {tab:?}")))?;
+
+        let view_current = &tabs[self.tabs.state];
+        let view_new = view_current.combine(&tabs[idx], op, &tabs[0])?;
+        self.open(view_new);
 
         Ok(())
     }
@@ -254,11 +836,184 @@ impl App {
 
         let view_current = self.tabs.selected();
         let view_new = view_current.subgraph()?;
-        self.tabs.open(view_new);
+        self.open(view_new);
+
+        Ok(())
+    }
+
+    /// Extract the strongly connected component selected in the cycles popup.
+    /// It opens a new tab with the cycle graph view.
+    pub fn cycle(&mut self) -> DotViewerResult<()> {
+        self.set_normal_mode();
+
+        let view_current = self.tabs.selected();
+        let view_new = view_current.cycle()?;
+        self.open(view_new);
+
+        Ok(())
+    }
+
+    /// Analyze the critical path of the current view, with weights from `attr` if given.
+    /// It opens a new tab highlighting the critical path.
+    pub fn critical(&mut self, attr: Option<String>) -> DotViewerResult<()> {
+        self.set_normal_mode();
+
+        let view_current = self.tabs.selected();
+        let view_new = view_current.critical(attr.as_deref())?;
+        self.open(view_new);
+
+        Ok(())
+    }
+
+    /// Open `view` in a new tab, as a child of the current tab.
+    fn open(&mut self, mut view: View) {
+        let parent = self.tabs.selected().id;
+
+        view.id = self.tabs.tabs.iter().map(|view| view.id).max().unwrap_or(0) + 1;
+        view.parent = Some(parent);
+
+        self.tabs.open(view);
+    }
+
+    /// Close the current tab,
+    /// asking whether to close the tabs derived from it too, if any.
+    pub fn close_tab(&mut self) -> DotViewerResult<()> {
+        let id = self.tabs.selected().id;
+        let descendants = self.descendants(id).len();
+
+        // the first tab cannot be closed, so there is nothing to ask
+        if self.tabs.state == 0 || descendants == 0 {
+            return self.tabs.close();
+        }
+
+        self.confirm = Some(Confirm::CloseTab { id, descendants });
+        self.set_popup_mode(PopupMode::Confirm);
+
+        Ok(())
+    }
+
+    /// Answer the question in the confirm popup.
+    pub fn answer(&mut self, yes: bool) -> DotViewerResult<Success> {
+        self.set_normal_mode();
+
+        let confirm = (self.confirm.take())
+            .ok_or(DotViewerError::ViewerError("nothing to confirm".to_string()))?;
+
+        match confirm {
+            Confirm::CloseTab { id, .. } => self.close_tabs(id, yes).map(|_| Success::default()),
+            Confirm::Overwrite { path } if yes => self.write_graph(&path),
+            Confirm::Render { path, engine } if yes => self.render_graph(&path, &engine),
+            Confirm::Overwrite { .. } | Confirm::Render { .. } => Ok(Success::default()),
+        }
+    }
+
+    /// Close the tab with `id`, and its descendants if `descendants` is set.
+    /// Otherwise its children are passed on to its parent.
+    /// The parent tab is selected afterwards.
+    fn close_tabs(&mut self, id: usize, descendants: bool) -> DotViewerResult<()> {
+        let tabs = &self.tabs.tabs;
+        let idx = (tabs.iter().position(|view| view.id == id))
+            .ok_or(DotViewerError::ViewerError("no such tab".to_string()))?;
+        if idx == 0 {
+            return Err(DotViewerError::ViewerError("cannot close the first tab".to_string()));
+        }
+
+        let parent = tabs[idx].parent;
+        let mut closing: HashSet<usize> = HashSet::from([id]);
+        if descendants {
+            closing.extend(self.descendants(id));
+        }
+
+        let tabs = &mut self.tabs.tabs;
+        tabs.retain(|view| !closing.contains(&view.id));
+        for view in tabs.iter_mut() {
+            if view.parent == Some(id) {
+                view.parent = parent;
+            }
+        }
+
+        self.tabs.state = (tabs.iter()).position(|view| Some(view.id) == parent).unwrap_or(0);
+
+        Ok(())
+    }
+
+    /// Collect ids of the tabs derived from the tab with `id`, directly or indirectly.
+    fn descendants(&self, id: usize) -> Vec<usize> {
+        let tabs = &self.tabs.tabs;
+
+        let mut descendants = Vec::new();
+        let mut frontier = vec![id];
+        while let Some(id) = frontier.pop() {
+            for view in tabs.iter().filter(|view| view.parent == Some(id)) {
+                descendants.push(view.id);
+                frontier.push(view.id);
+            }
+        }
+
+        descendants
+    }
+
+    /// Toggle how the attrs panel shows the selected node, between rendered, list and raw.
+    pub fn toggle_attrs(&mut self) {
+        let view = self.tabs.selected();
+        view.attrs = view.attrs.next();
+    }
+
+    /// Navigate to the tab that the current tab was derived from,
+    /// keeping the same node selected.
+    pub fn goto_parent(&mut self) -> DotViewerResult<()> {
+        let view = self.tabs.selected();
+        let parent = (view.parent)
+            .ok_or(DotViewerError::ViewerError("the first tab has no parent".to_string()))?;
+        let id = view.current_id();
+
+        let idx = (self.tabs.tabs.iter().position(|view| view.id == parent))
+            .ok_or(DotViewerError::ViewerError("the parent tab is closed".to_string()))?;
+        self.tabs.select(idx);
+
+        let view = self.tabs.selected();
+        view.goto(&id)
+    }
+
+    /// Navigate to the tab selected in the lineage popup.
+    pub fn goto_lineage_selected(&mut self) -> DotViewerResult<()> {
+        self.set_normal_mode();
+
+        let (id, _) = (self.lineage.selected())
+            .ok_or(DotViewerError::ViewerError("no tab selected".to_string()))?;
+        let idx = (self.tabs.tabs.iter().position(|view| view.id == id))
+            .ok_or(DotViewerError::ViewerError("the tab is closed".to_string()))?;
+        self.tabs.select(idx);
 
         Ok(())
     }
 
+    /// Update the lineage of tabs, listing each tab after its parent with its depth,
+    /// and select the current tab.
+    fn update_lineage(&mut self) {
+        let tabs = &self.tabs.tabs;
+
+        let mut lineage = Vec::new();
+        let mut stack = vec![(tabs[0].id, 0)];
+        while let Some((id, depth)) = stack.pop() {
+            lineage.push((id, depth));
+
+            let children = (tabs.iter())
+                .filter(|view| view.parent == Some(id))
+                .map(|view| (view.id, depth + 1));
+            // push in reverse, so that children are listed in the order they were opened
+            stack.extend(children.rev());
+        }
+
+        let current = tabs[self.tabs.state].id;
+        let state = lineage.iter().position(|(id, _)| *id == current);
+
+        self.lineage = List::from_iter(lineage);
+        if let Some(state) = state {
+            self.lineage.select(state);
+        }
+    }
+
     pub fn set_normal_mode(&mut self) {
         self.mode = Mode::Normal;
     }
@@ -267,6 +1022,10 @@ impl App {
         self.input.clear();
 
         self.mode = Mode::Command;
+
+        if let Some(history) = self.histories.get_mut(&self.mode) {
+            history.reset();
+        }
     }
 
     pub fn set_search_mode(&mut self, smode: SearchMode) {
@@ -274,8 +1033,15 @@ impl App {
 
         self.mode = Mode::Search(smode);
 
+        if let Some(history) = self.histories.get_mut(&self.mode) {
+            history.reset();
+        }
+
         let view = self.tabs.selected();
 
+        let from = view.current_id();
+        view.jumps.record(from);
+
         view.matches = List::from_iter(Vec::new());
         view.prevs = List::from_iter(Vec::new());
         view.nexts = List::from_iter(Vec::new());
@@ -286,25 +1052,73 @@ impl App {
     }
 }
 
-fn valid_filename(filename: &str) -> bool {
-    (!filename.contains('/')) && filename.ends_with(".dot")
-}
+/// Rebuild the tabs snapshotted in `tabs` by replaying their recipes on `root`,
+/// the rebuilt root tab, and restoring each from its snapshot.
+/// Returns the tabs, the index of the tab at `selected` (or of the nearest one before it),
+/// and the titles of the tabs that cannot be derived any more, which are dropped.
+fn rebuild(mut root: View, tabs: Vec<Tab>, selected: usize) -> (Vec<View>, usize, Vec<String>) {
+    let mut tabs = tabs.into_iter();
+    let root_tab = tabs.next();
+
+    let mut state = 0;
+    let mut views = Vec::new();
+    let mut dropped = Vec::new();
+    for (idx, tab) in tabs.enumerate() {
+        match root.replay(&tab.recipe) {
+            Ok(mut view) => {
+                view.resume(tab);
+                views.push(view);
+
+                if idx < selected {
+                    state = views.len();
+                }
+            }
+            Err(_) => dropped.push(tab.title),
+        }
+    }
 
-fn write_graph(filename: String, graph: &Graph) -> DotViewerResult<Success> {
-    if !valid_filename(&filename) {
-        return Err(DotViewerError::CommandError(format!("invalid dot filename: {filename}")));
+    // replaying searches on the root view, so restore its own search last
+    if let Some(tab) = root_tab {
+        root.resume(tab);
     }
+    views.insert(0, root);
 
-    let mut open_options = fs::OpenOptions::new();
-    let open_options = open_options.write(true).truncate(true).create(true);
+    (views, state, dropped)
+}
+
+/// Load the graph at `path`, or the union graph of `before` and `path` with their changes
+/// if comparing them.
+fn load(path: &str, before: Option<&str>) -> DotViewerResult<(Graph, Option<Diff>)> {
+    let Some(before) = before else {
+        return Ok((load_graph(path)?, None));
+    };
+
+    let graph_before = load_graph(before)?;
+    let graph_after = load_graph(path)?;
 
-    fs::create_dir_all("./exports")?;
+    // the union graph is parsed back from a temporary file, removed once parsed
+    let temp = env::temp_dir().join(format!("dot-viewer-diff-{}.dot", process::id()));
+    let result = write_union(&graph_before, &graph_after, &temp);
+    let _ = fs::remove_file(&temp);
+
+    result.map(|(graph, diff)| (graph, Some(diff)))
+}
 
-    let mut file_export = open_options.open(format!("./exports/{filename}"))?;
-    graph.to_dot(&mut file_export)?;
+fn write_union(before: &Graph, after: &Graph, temp: &Path) -> DotViewerResult<(Graph, Diff)> {
+    let diff = Diff::new(before, after, &mut fs::File::create(temp)?)?;
+    let graph = load_graph(&temp.to_string_lossy())?;
 
-    let mut file_current = open_options.open("./exports/current.dot")?;
-    graph.to_dot(&mut file_current)?;
+    Ok((graph, diff))
+}
+
+/// Title of the root view, naming both files when comparing them.
+fn root_title(graph: &Graph, path: &str, before: Option<&str>) -> String {
+    match before {
+        Some(before) => format!("{before} -> {path}"),
+        None => graph.id().clone(),
+    }
+}
 
-    Ok(Success::ExportSuccess(filename))
+fn modified(path: &str) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
 }
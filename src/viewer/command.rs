@@ -1,14 +1,28 @@
-use crate::viewer::utils::Trie;
-use clap::builder::{Arg, Command as ClapCommand};
+use crate::viewer::{
+    recipe::SetOp,
+    utils::{Direction, Trie, ENGINES},
+};
+use clap::builder::{Arg, ArgAction, Command as ClapCommand};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub(crate) enum Command {
     Neighbors(Neighbors),
+    Cone(Cone),
+    Path(Path),
     Export(Export),
-    Xdot(Xdot),
+    Render(Render),
+    Mksession(Mksession),
+    View(View),
     Filter,
+    Combine(Combine),
     Help,
     Subgraph,
+    Jumps,
+    Marks,
+    Lineage,
+    Stats,
+    Cycles,
+    Critical(Critical),
     NoMatch,
 }
 
@@ -17,30 +31,88 @@ pub(crate) struct Neighbors {
     pub(crate) depth: Option<usize>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct Cone {
+    pub(crate) direction: Direction,
+    pub(crate) depth: Option<usize>,
+    pub(crate) matches: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct Path {
+    pub(crate) from: Option<String>,
+    pub(crate) to: Option<String>,
+    pub(crate) shortest: bool,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub(crate) struct Export {
     pub(crate) filename: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub(crate) struct Xdot {
+pub(crate) struct Render {
+    pub(crate) filename: Option<String>,
+    pub(crate) engine: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct Mksession {
     pub(crate) filename: Option<String>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct View {
+    pub(crate) filename: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct Combine {
+    pub(crate) op: SetOp,
+    pub(crate) tab: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct Critical {
+    pub(crate) attr: Option<String>,
+}
+
 pub(crate) struct CommandTrie {
     pub(crate) trie_cmd: Trie,
     pub(crate) _trie_arg: Trie,
 }
 
-fn subcommands() -> [ClapCommand; 6] {
+fn subcommands() -> [ClapCommand; 20] {
     [
         ClapCommand::new("neighbors")
             .arg(Arg::new("depth").value_parser(clap::value_parser!(usize))),
+        ClapCommand::new("ancestors")
+            .arg(Arg::new("depth").value_parser(clap::value_parser!(usize)))
+            .arg(Arg::new("matches").short('m').long("matches").action(ArgAction::SetTrue)),
+        ClapCommand::new("descendants")
+            .arg(Arg::new("depth").value_parser(clap::value_parser!(usize)))
+            .arg(Arg::new("matches").short('m').long("matches").action(ArgAction::SetTrue)),
+        ClapCommand::new("path")
+            .arg(Arg::new("nodes").num_args(1..=2))
+            .arg(Arg::new("shortest").short('s').long("shortest").action(ArgAction::SetTrue)),
         ClapCommand::new("export").arg(Arg::new("filename")),
-        ClapCommand::new("xdot").arg(Arg::new("filename")),
+        ClapCommand::new("render")
+            .arg(Arg::new("filename"))
+            .arg(Arg::new("engine").short('e').long("engine").value_parser(ENGINES)),
+        ClapCommand::new("mksession").arg(Arg::new("filename")),
+        ClapCommand::new("view").alias("xdot").arg(Arg::new("filename")),
         ClapCommand::new("filter"),
+        ClapCommand::new("union").arg(Arg::new("tab").num_args(1..).allow_hyphen_values(true)),
+        ClapCommand::new("intersect").arg(Arg::new("tab").num_args(1..).allow_hyphen_values(true)),
+        ClapCommand::new("diff").arg(Arg::new("tab").num_args(1..).allow_hyphen_values(true)),
         ClapCommand::new("help"),
         ClapCommand::new("subgraph"),
+        ClapCommand::new("jumps"),
+        ClapCommand::new("marks"),
+        ClapCommand::new("lineage"),
+        ClapCommand::new("stats"),
+        ClapCommand::new("cycles"),
+        ClapCommand::new("critical").arg(Arg::new("attr")),
     ]
 }
 
@@ -64,21 +136,77 @@ impl Command {
 
                     Self::Neighbors(neigbors)
                 }
+                Some((name @ ("ancestors" | "descendants"), matches)) => {
+                    let direction =
+                        if name == "ancestors" { Direction::Backward } else { Direction::Forward };
+                    let depth = matches.get_one::<usize>("depth").copied();
+                    let cone = Cone { direction, depth, matches: matches.get_flag("matches") };
+
+                    Self::Cone(cone)
+                }
+                Some(("path", matches)) => {
+                    let mut nodes: Vec<String> = (matches.get_many::<String>("nodes"))
+                        .map(|nodes| nodes.cloned().collect())
+                        .unwrap_or_default();
+                    let to = nodes.pop();
+                    let from = nodes.pop();
+                    let shortest = matches.get_flag("shortest");
+                    let path = Path { from, to, shortest };
+
+                    Self::Path(path)
+                }
                 Some(("export", matches)) => {
                     let filename = matches.get_one::<String>("filename").cloned();
                     let export = Export { filename };
 
                     Self::Export(export)
                 }
-                Some(("xdot", matches)) => {
+                Some(("render", matches)) => {
+                    let filename = matches.get_one::<String>("filename").cloned();
+                    let engine = matches.get_one::<String>("engine").cloned();
+                    let render = Render { filename, engine };
+
+                    Self::Render(render)
+                }
+                Some(("mksession", matches)) => {
+                    let filename = matches.get_one::<String>("filename").cloned();
+                    let mksession = Mksession { filename };
+
+                    Self::Mksession(mksession)
+                }
+                Some(("view", matches)) => {
                     let filename = matches.get_one::<String>("filename").cloned();
-                    let xdot = Xdot { filename };
+                    let view = View { filename };
 
-                    Self::Xdot(xdot)
+                    Self::View(view)
                 }
                 Some(("filter", _)) => Self::Filter,
+                Some((name @ ("union" | "intersect" | "diff"), matches)) => {
+                    let op = match name {
+                        "union" => SetOp::Union,
+                        "intersect" => SetOp::Intersect,
+                        _ => SetOp::Diff,
+                    };
+                    // tab titles may contain whitespaces
+                    let tab = (matches.get_many::<String>("tab"))
+                        .map(|words| words.cloned().collect::<Vec<_>>().join(" "));
+                    let combine = Combine { op, tab };
+
+                    Self::Combine(combine)
+                }
                 Some(("help", _)) => Self::Help,
                 Some(("subgraph", _)) => Self::Subgraph,
+                Some(("jumps", _)) => Self::Jumps,
+                Some(("marks", _)) => Self::Marks,
+                Some(("lineage", _)) => Self::Lineage,
+                Some(("stats", _)) => Self::Stats,
+                Some(("cycles", _)) => Self::Cycles,
+                Some(("critical", matches)) => {
+                    let attr = matches.get_one::<String>("attr").cloned();
+                    let critical = Critical { attr };
+
+                    Self::Critical(critical)
+                }
                 _ => unreachable!(),
             },
             Err(_) => Self::NoMatch,
@@ -0,0 +1,26 @@
+use std::path::PathBuf;
+
+/// `Confirm` is an action waiting for the user to confirm it in the confirm popup.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Confirm {
+    /// Close the tab with `id`, and also its `descendants` tabs if confirmed
+    CloseTab { id: usize, descendants: usize },
+    /// Export the current tab to `path`, overwriting the existing file
+    Overwrite { path: PathBuf },
+    /// Render the current tab to `path` with the layout `engine`, overwriting the existing file
+    Render { path: PathBuf, engine: String },
+}
+
+impl Confirm {
+    /// Question to ask the user, answered by yes or no.
+    pub fn prompt(&self) -> String {
+        match self {
+            Self::CloseTab { descendants, .. } => {
+                format!("Close {descendants} tab(s) derived from this tab too?")
+            }
+            Self::Overwrite { path } | Self::Render { path, .. } => {
+                format!("Overwrite {}?", path.display())
+            }
+        }
+    }
+}
@@ -0,0 +1,211 @@
+use crate::viewer::error::DotViewerResult;
+
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+
+use dot_graph::{Edge, Graph, Node};
+
+pub(crate) type Attrs = HashMap<String, String>;
+
+/// `Change` is how a node or an edge differs between two graphs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Change {
+    Added,
+    Removed,
+    Changed { before: Attrs, after: Attrs },
+}
+
+/// `Diff` holds the changes of nodes and edges from a graph `before` to a graph `after`.
+pub(crate) struct Diff {
+    /// Changes of nodes, by node id
+    pub nodes: HashMap<String, Change>,
+    /// Changes of each of the parallel edges between two nodes, by their endpoint ids,
+    /// `None` for edges that did not change
+    pub edges: HashMap<(String, String), Vec<Option<Change>>>,
+    /// Nodes that changed, or have an incident edge that changed
+    touched: HashSet<String>,
+}
+
+impl Diff {
+    /// Compare `before` and `after`, writing their union graph in dot format to `writer`.
+    ///
+    /// The union graph is `after` as it is, with its subgraphs and attributes,
+    /// plus the nodes and edges removed from `before`.
+    /// Removed nodes are put back in the subgraphs they were in before.
+    pub fn new(before: &Graph, after: &Graph, writer: &mut dyn Write) -> DotViewerResult<Self> {
+        let nodes_before = collect_nodes(before)?;
+        let nodes_after = collect_nodes(after)?;
+        let edges_before = collect_edges(before)?;
+        let edges_after = collect_edges(after)?;
+
+        let nodes = compare(&nodes_before, &nodes_after, |node| node.attrs());
+
+        // parallel edges are compared in the order they appear in each graph
+        let mut edges = HashMap::new();
+        let mut removed_edges = Vec::new();
+        for (key, prevs) in &edges_before {
+            let nexts = edges_after.get(key).map_or(&[][..], Vec::as_slice);
+            removed_edges.extend(prevs.iter().skip(nexts.len()));
+        }
+        let mut keys = Vec::from_iter(edges_before.keys().chain(edges_after.keys()));
+        keys.sort_unstable();
+        keys.dedup();
+        for key in keys {
+            let prevs = edges_before.get(key).map_or(&[][..], Vec::as_slice);
+            let nexts = edges_after.get(key).map_or(&[][..], Vec::as_slice);
+
+            let changes: Vec<Option<Change>> = (0..prevs.len().max(nexts.len()))
+                .map(|idx| match (prevs.get(idx), nexts.get(idx)) {
+                    (Some(prev), Some(next)) if prev.attrs() != next.attrs() => {
+                        let before = prev.attrs().clone();
+                        let after = next.attrs().clone();
+                        Some(Change::Changed { before, after })
+                    }
+                    (Some(_), Some(_)) => None,
+                    (None, _) => Some(Change::Added),
+                    (_, None) => Some(Change::Removed),
+                })
+                .collect();
+
+            if changes.iter().any(Option::is_some) {
+                edges.insert(key.clone(), changes);
+            }
+        }
+
+        let mut touched: HashSet<String> = nodes.keys().cloned().collect();
+        for (from, to) in edges.keys() {
+            touched.insert(from.clone());
+            touched.insert(to.clone());
+        }
+
+        // write `after`, reopening it to append what was removed before the closing brace
+        let mut dot = Vec::new();
+        after.to_dot(&mut dot)?;
+        let dot = String::from_utf8_lossy(&dot);
+        let dot = dot.trim_end().strip_suffix('}').unwrap_or(&dot);
+        write!(writer, "{dot}")?;
+
+        let removed_nodes: HashMap<&String, &Node> = (nodes_before.iter())
+            .filter(|(id, _)| !nodes_after.contains_key(*id))
+            .map(|(id, &node)| (id, node))
+            .collect();
+        write_removed(before, before.id(), &removed_nodes, 0, writer)?;
+
+        for edge in removed_edges {
+            edge.to_dot(1, writer)?;
+        }
+        writeln!(writer, "}}")?;
+
+        Ok(Self { nodes, edges, touched })
+    }
+
+    /// Changes of the parallel edges from `from` to `to`, if any changed.
+    pub fn edge(&self, from: &str, to: &str) -> Option<&[Option<Change>]> {
+        self.edges.get(&(from.to_string(), to.to_string())).map(Vec::as_slice)
+    }
+
+    /// Whether the node `id` or any of its incident edges changed.
+    pub fn touches(&self, id: &str) -> bool {
+        self.touched.contains(id)
+    }
+}
+
+fn collect_nodes(graph: &Graph) -> DotViewerResult<HashMap<String, &Node>> {
+    let nodes = graph.collect_nodes(graph.id())?;
+
+    Ok(nodes.into_iter().map(|node| (node.id().clone(), node)).collect())
+}
+
+fn collect_edges(graph: &Graph) -> DotViewerResult<HashMap<(String, String), Vec<&Edge>>> {
+    let mut edges: HashMap<(String, String), Vec<&Edge>> = HashMap::new();
+    for edge in graph.collect_edges(graph.id())? {
+        edges.entry((edge.from().clone(), edge.to().clone())).or_default().push(edge);
+    }
+
+    Ok(edges)
+}
+
+/// Write the `removed` nodes in the subgraph `id` of `graph`,
+/// nested in the same subgraphs as they were in `graph`.
+fn write_removed(
+    graph: &Graph,
+    id: &String,
+    removed: &HashMap<&String, &Node>,
+    depth: usize,
+    writer: &mut dyn Write,
+) -> DotViewerResult<()> {
+    let mut nodes = removed_in(graph, id, removed)?;
+    if nodes.is_empty() {
+        return Ok(());
+    }
+
+    // subgraphs of the same id are merged in dot, so reopening them is safe
+    let root = id == graph.id();
+    let indent = "\t".repeat(depth);
+    if !root {
+        writeln!(writer, "{indent}subgraph {id} {{")?;
+    }
+
+    for child in graph.collect_subgraphs(id)? {
+        for node in removed_in(graph, child, removed)? {
+            nodes.remove(node);
+        }
+        write_removed(graph, child, removed, depth + 1, writer)?;
+    }
+
+    let mut nodes = Vec::from_iter(nodes);
+    nodes.sort_unstable();
+    for node in nodes {
+        removed[node].to_dot(depth + 1, writer)?;
+    }
+
+    if !root {
+        writeln!(writer, "{indent}}}")?;
+    }
+
+    Ok(())
+}
+
+/// Ids of the `removed` nodes in the subgraph `id` of `graph`.
+fn removed_in<'a>(
+    graph: &'a Graph,
+    id: &String,
+    removed: &HashMap<&String, &Node>,
+) -> DotViewerResult<HashSet<&'a String>> {
+    let nodes = graph.collect_nodes(id)?;
+
+    Ok(nodes.into_iter().map(|node| node.id()).filter(|id| removed.contains_key(id)).collect())
+}
+
+fn compare<K, T>(
+    before: &HashMap<K, &T>,
+    after: &HashMap<K, &T>,
+    attrs: impl Fn(&T) -> &Attrs,
+) -> HashMap<K, Change>
+where
+    K: Clone + Eq + std::hash::Hash,
+{
+    let mut changes = HashMap::new();
+
+    for (key, &item) in after {
+        match before.get(key) {
+            None => {
+                changes.insert(key.clone(), Change::Added);
+            }
+            Some(&prev) if attrs(prev) != attrs(item) => {
+                let before = attrs(prev).clone();
+                let after = attrs(item).clone();
+                changes.insert(key.clone(), Change::Changed { before, after });
+            }
+            Some(_) => {}
+        }
+    }
+
+    for key in before.keys() {
+        if !after.contains_key(key) {
+            changes.insert(key.clone(), Change::Removed);
+        }
+    }
+
+    changes
+}
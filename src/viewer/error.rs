@@ -16,8 +16,18 @@ pub enum DotViewerError {
     CommandError(String),
     #[error("Err: no keybinding for {0:?}")]
     KeyError(KeyCode),
+    #[error("Err: invalid keymap {0}")]
+    KeymapError(String),
+    #[error("Err: invalid config {0}")]
+    ConfigError(String),
+    #[error("Err: invalid query, {0}")]
+    QueryError(String),
+    #[error("Err: invalid session {0}")]
+    SessionError(String),
+    #[error("Err: graphviz failed to {0}")]
+    RenderError(String),
     #[error(transparent)]
     IOError(#[from] std::io::Error),
-    #[error("Err: failed to launch xdot.py")]
-    XdotError,
+    #[error("Err: failed to launch {0}")]
+    LaunchError(String),
 }
@@ -7,27 +7,84 @@ pub(super) const ROWS: &[&[&str]] = &[
     &["All", "esc", "", "go back to Normal mode"],
     &["Normal", "/", "", "go to fuzzy search mode"],
     &["Normal", "r", "", "go to regex search mode"],
+    &["Normal", "?", "", "go to query search mode"],
     &["Normal", ":", "", "go to command mode"],
     &["", "", "", ""],
-    &["Normal", "c", "", "close the current tab (view)"],
-    &["", "h/l", "", "move focus between current, prevs, nexts list"],
+    &["Normal", "c", "", "close the current tab (view), asking to close its derived tabs"],
+    &["", "gp", "", "go to the parent tab (view) with the same node selected"],
+    &["", "h/l", "", "move focus between current, prevs, nexts list, showing edge attrs"],
     &["", "j/k", "", "traverse in focused list"],
     &["", "n/N", "", "go to next/previous match"],
+    &["", "ctrl+o/ctrl+n", "", "go back/forward in the jump list"],
+    &["", "m{a-z}", "", "mark the current node"],
+    &["", "'{a-z}", "", "go to the marked node"],
+    &["", "]c/[c", "", "go to next/previous changed node when comparing graphs"],
+    &["", "t", "", "toggle attrs between rendered label, attribute list and raw dot"],
     &["", "tab/backtab", "", "move between tabs"],
     &["Search", "tab", "", "autocomplete search keyword"],
+    &["", "up/down", "", "recall search keyword from history"],
+    &["", "ctrl+r", "", "search history backwards"],
     &["", "enter", "", "apply search"],
     &["Command", "", "filter", "apply filter on current matches"],
+    &["", "", "union [tab]", "union of the current tab and the tab, by title or index from 1"],
+    &["", "", "intersect [tab]", "intersection of the current tab and the tab"],
+    &["", "", "diff [tab]", "nodes of the current tab not in the tab"],
     &["", "", "neighbors [depth]", "get up to [depth] neighbors of the current node"],
-    &["", "", "export [(opt) filename]", "export the current tab (view) to dot"],
     &[
         "",
         "",
-        "xdot [(opt) filename]",
-        "launch xdot, showing the most current exported file on default",
+        "ancestors [(opt) depth] [(opt) -m]",
+        "get ancestors of the current node (or matches)",
+    ],
+    &[
+        "",
+        "",
+        "descendants [(opt) depth] [(opt) -m]",
+        "get descendants of the current node (or matches)",
+    ],
+    &["", "", "path [(opt) from] [to]", "get nodes on paths between two nodes"],
+    &["", "", "path -s [(opt) from] [to]", "get nodes on a shortest path between two nodes"],
+    &[
+        "",
+        "",
+        "export [(opt) filename]",
+        "export the current tab (view) to dot, json, graphml, mmd or gml",
+    ],
+    &[
+        "",
+        "",
+        "render [(opt) filename] [(opt) -e engine]",
+        "render the current tab (view) to svg, png or pdf with dot, neato or sfdp",
+    ],
+    &["", "", "mksession [filename]", "save the open tabs and marks as a session"],
+    &[
+        "",
+        "",
+        "view [(opt) filename]",
+        "launch the external viewer on the filename, or on the current tab (view) on default",
     ],
     &["", "", "subgraph", "go to subgraph Popup mode"],
+    &["", "", "jumps", "go to jump list Popup mode"],
+    &["", "", "marks", "go to marks Popup mode"],
+    &["", "", "lineage", "go to tab lineage Popup mode"],
+    &["", "", "stats", "go to statistics Popup mode"],
+    &["", "", "cycles", "go to cycles Popup mode"],
+    &["", "", "critical [(opt) attr]", "get the longest path weighted by attr in a new tab"],
     &["", "tab", "", "autocomplete command"],
+    &["", "up/down", "", "recall command from history"],
+    &["", "ctrl+r", "", "search history backwards"],
     &["", "enter", "", "execute command"],
     &["Subgraph Popup", "h/j/k/l", "", "traverse tree"],
     &["", "enter", "", "change root to the selected subgraph"],
+    &["Jumps Popup", "j/k", "", "traverse jump list"],
+    &["", "enter", "", "go to the selected jump"],
+    &["Marks Popup", "j/k", "", "traverse marks"],
+    &["", "enter", "", "go to the selected mark"],
+    &["Lineage Popup", "j/k", "", "traverse the tab lineage"],
+    &["", "enter", "", "go to the selected tab"],
+    &["Confirm Popup", "y/n", "", "answer yes/no, or esc to cancel"],
+    &["Stats Popup", "j/k", "", "traverse statistics"],
+    &["", "enter", "", "go to the node of the selected row"],
+    &["Cycles Popup", "j/k", "", "traverse cycles"],
+    &["", "enter", "", "open the selected cycle as a new tab"],
 ];
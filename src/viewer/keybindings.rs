@@ -1,8 +1,10 @@
 use crate::viewer::{
     app::App,
     error::{DotViewerError, DotViewerResult},
+    keymap::{self, Action, Lookup},
     modes::{Mode, PopupMode, SearchMode},
     success::Success,
+    utils::Query,
     view::{Focus, View},
 };
 
@@ -14,51 +16,119 @@ impl App {
         info!("{:?}", key.code);
 
         self.result = match key.code {
-            KeyCode::Char(c) => self.char(c).map(|_| Success::default()),
-            KeyCode::Enter => self.enter(),
-            KeyCode::Backspace => self.backspace().map(|_| Success::default()),
-            KeyCode::Esc => self.esc().map(|_| Success::default()),
-            KeyCode::Tab => self.tab().map(|_| Success::default()),
-            KeyCode::BackTab => self.backtab().map(|_| Success::default()),
-            KeyCode::Right => self.right().map(|_| Success::default()),
-            KeyCode::Left => self.left().map(|_| Success::default()),
-            _ => Ok(Success::default()),
+            KeyCode::Esc => {
+                self.pending.clear();
+                self.operator = None;
+                self.esc().map(|_| Success::default())
+            }
+            _ => match &self.mode {
+                Mode::Normal | Mode::Popup(_) => self.lookup(key),
+                Mode::Command | Mode::Search(_) => self.edit(key),
+            },
         };
 
         if let Err(err) = &self.result {
             warn!("{err}");
         }
+    }
+
+    /// Look up the key sequence typed so far in the keymap, running the bound action if any.
+    fn lookup(&mut self, key: KeyEvent) -> DotViewerResult<Success> {
+        if let Some(action) = self.operator.take() {
+            return match key.code {
+                KeyCode::Char(c) => self.act_on(action, c).map(|_| Success::default()),
+                _ => Err(DotViewerError::KeyError(key.code)),
+            };
+        }
 
-        self.lookback = Some(key.code);
+        self.pending.push(keymap::token(&key));
+
+        match self.keymap.lookup(&self.mode, &self.pending) {
+            Lookup::Action(action) => {
+                self.pending.clear();
+                self.act(action)
+            }
+            Lookup::Pending => Ok(Success::default()),
+            Lookup::None => {
+                self.pending.clear();
+                Err(DotViewerError::KeyError(key.code))
+            }
+        }
     }
 
-    fn char(&mut self, c: char) -> DotViewerResult<()> {
-        match &self.mode {
-            Mode::Normal => self.char_normal(c)?,
-            Mode::Command => self.char_command(c)?,
-            Mode::Search(_) => self.char_search(c),
-            Mode::Popup(_) => self.char_popup(c)?,
+    fn act(&mut self, action: Action) -> DotViewerResult<Success> {
+        match action {
+            Action::Quit => self.quit = true,
+            Action::FuzzySearch => self.set_search_mode(SearchMode::Fuzzy),
+            Action::RegexSearch => self.set_search_mode(SearchMode::Regex),
+            Action::QuerySearch => self.set_search_mode(SearchMode::Query),
+            Action::Command => self.set_command_mode(),
+            Action::CloseTab => self.close_tab()?,
+            Action::NextTab => self.tabs.next(),
+            Action::PrevTab => self.tabs.previous(),
+            Action::ParentTab => self.goto_parent()?,
+            Action::Left => self.left()?,
+            Action::Down => self.down()?,
+            Action::Up => self.up()?,
+            Action::Right => self.right()?,
+            Action::NextMatch => self.goto_next_match()?,
+            Action::PrevMatch => self.goto_prev_match()?,
+            Action::First => self.goto_first()?,
+            Action::Last => self.goto_last()?,
+            Action::Enter => return self.enter(),
+            Action::JumpBack => self.jump_back()?,
+            Action::JumpForward => self.jump_forward()?,
+            Action::NextChange => self.goto_next_change()?,
+            Action::PrevChange => self.goto_prev_change()?,
+            Action::ToggleAttrs => self.toggle_attrs(),
+            Action::Yes => return self.answer(true),
+            Action::No => return self.answer(false),
+            Action::Delete => self.backspace()?,
+            Action::Complete => self.tab()?,
+            Action::HistoryPrev => self.recall_prev(),
+            Action::HistoryNext => self.recall_next(),
+            Action::HistorySearch => self.recall_search()?,
+            Action::SetMark | Action::GotoMark => self.operator = Some(action),
         };
 
-        Ok(())
+        Ok(Success::default())
     }
 
-    fn char_normal(&mut self, c: char) -> DotViewerResult<()> {
-        match c {
-            'q' => self.quit = true,
-            '/' => self.set_search_mode(SearchMode::Fuzzy),
-            'r' => self.set_search_mode(SearchMode::Regex),
-            ':' => self.set_command_mode(),
-            'c' => self.tabs.close()?,
-            'h' => self.left()?,
-            'j' => self.down()?,
-            'k' => self.up()?,
-            'l' => self.right()?,
-            'n' => self.goto_next_match()?,
-            'N' => self.goto_prev_match()?,
-            'g' => self.goto_first()?,
-            'G' => self.goto_last()?,
-            _ => Err(DotViewerError::KeyError(KeyCode::Char(c)))?,
+    /// Run an action taking a character argument `c`.
+    fn act_on(&mut self, action: Action, c: char) -> DotViewerResult<()> {
+        match action {
+            Action::SetMark => self.set_mark(c),
+            Action::GotoMark => self.goto_mark(c),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Edit the input form in command and search modes,
+    /// running the action bound to a single key, or else typing it in.
+    fn edit(&mut self, key: KeyEvent) -> DotViewerResult<Success> {
+        let action = match self.keymap.lookup(&self.mode, &[keymap::token(&key)]) {
+            Lookup::Action(action) => Some(action),
+            Lookup::Pending | Lookup::None => None,
+        };
+
+        if action != Some(Action::HistorySearch) {
+            if let Some(history) = self.histories.get_mut(&self.mode) {
+                history.stop_search();
+            }
+        }
+
+        match (action, key.code) {
+            (Some(action), _) => self.act(action),
+            (None, KeyCode::Char(c)) => self.char(c).map(|_| Success::default()),
+            _ => Ok(Success::default()),
+        }
+    }
+
+    fn char(&mut self, c: char) -> DotViewerResult<()> {
+        match &self.mode {
+            Mode::Command => self.char_command(c)?,
+            Mode::Search(_) => self.char_search(c),
+            _ => unreachable!(),
         };
 
         Ok(())
@@ -74,55 +144,38 @@ impl App {
         self.update_search();
     }
 
-    fn char_popup(&mut self, c: char) -> DotViewerResult<()> {
-        match &self.mode {
-            Mode::Popup(pmode) => match pmode {
-                PopupMode::Tree => self.char_tree(c),
-                PopupMode::Help => self.char_help(c),
-            },
-            _ => unreachable!(),
-        }
-    }
-
-    fn char_tree(&mut self, c: char) -> DotViewerResult<()> {
-        match c {
-            'q' => {
-                self.quit = true;
-                Ok(())
-            }
-            'h' => self.left(),
-            'j' => self.down(),
-            'k' => self.up(),
-            'l' => self.right(),
-            _ => Err(DotViewerError::KeyError(KeyCode::Char(c))),
-        }
-    }
-
-    fn char_help(&mut self, c: char) -> DotViewerResult<()> {
-        match c {
-            'q' => {
-                self.quit = true;
-                Ok(())
-            }
-            'j' => self.down(),
-            'k' => self.up(),
-            _ => Err(DotViewerError::KeyError(KeyCode::Char(c))),
-        }
-    }
-
     fn enter(&mut self) -> DotViewerResult<Success> {
         match &self.mode {
             Mode::Normal => {
                 let view = self.tabs.selected();
                 view.enter().map(|_| Success::default())
             }
-            Mode::Command => self.exec(),
-            Mode::Search(_) => {
+            Mode::Command => {
+                self.save_history();
+                self.exec()
+            }
+            Mode::Search(smode) => {
+                let smode = smode.clone();
+
+                self.save_history();
                 self.set_normal_mode();
+
+                // report why an invalid query matches nothing, once it is applied
+                let key = &self.input.key;
+                if smode == SearchMode::Query && !key.is_empty() {
+                    let view = self.tabs.selected();
+                    Query::new(key, &view.graph)?;
+                }
+
                 Ok(Success::default())
             }
             Mode::Popup(pmode) => match pmode {
                 PopupMode::Tree => self.subgraph().map(|_| Success::default()),
+                PopupMode::Jumps => self.jump_selected().map(|_| Success::default()),
+                PopupMode::Marks => self.goto_mark_selected().map(|_| Success::default()),
+                PopupMode::Lineage => self.goto_lineage_selected().map(|_| Success::default()),
+                PopupMode::Stats => self.goto_stat_selected().map(|_| Success::default()),
+                PopupMode::Cycles => self.cycle().map(|_| Success::default()),
                 _ => Ok(Success::default()),
             },
         }
@@ -153,11 +206,11 @@ impl App {
 
     fn tab(&mut self) -> DotViewerResult<()> {
         match &self.mode {
-            Mode::Normal => self.tabs.next(),
             Mode::Command => self.autocomplete_command(),
             Mode::Search(smode) => match smode {
                 SearchMode::Fuzzy => self.autocomplete_fuzzy(),
                 SearchMode::Regex => self.autocomplete_regex(),
+                SearchMode::Query => {}
             },
             _ => Err(DotViewerError::KeyError(KeyCode::Tab))?,
         };
@@ -165,23 +218,19 @@ impl App {
         Ok(())
     }
 
-    fn backtab(&mut self) -> DotViewerResult<()> {
-        match &self.mode {
-            Mode::Normal => {
-                self.tabs.previous();
-                Ok(())
-            }
-            _ => Err(DotViewerError::KeyError(KeyCode::BackTab)),
-        }
-    }
-
-    fn up(&mut self) -> DotViewerResult<()> {
+    pub(super) fn up(&mut self) -> DotViewerResult<()> {
         let view = self.tabs.selected();
 
         match &self.mode {
             Mode::Normal => view.up()?,
             Mode::Popup(pmode) => match pmode {
                 PopupMode::Tree => view.subtree.up(),
+                PopupMode::Jumps => view.jumps.list.previous(),
+                PopupMode::Marks => self.marks.list.previous(),
+                PopupMode::Lineage => self.lineage.previous(),
+                PopupMode::Stats => self.stats.list.previous(),
+                PopupMode::Cycles => view.cycles.previous(),
+                PopupMode::Confirm => {}
                 PopupMode::Help => self.help.previous(),
             },
             _ => Err(DotViewerError::KeyError(KeyCode::Up))?,
@@ -190,13 +239,19 @@ impl App {
         Ok(())
     }
 
-    fn down(&mut self) -> DotViewerResult<()> {
+    pub(super) fn down(&mut self) -> DotViewerResult<()> {
         let view = self.tabs.selected();
 
         match &self.mode {
             Mode::Normal => view.down()?,
             Mode::Popup(pmode) => match pmode {
                 PopupMode::Tree => view.subtree.down(),
+                PopupMode::Jumps => view.jumps.list.next(),
+                PopupMode::Marks => self.marks.list.next(),
+                PopupMode::Lineage => self.lineage.next(),
+                PopupMode::Stats => self.stats.list.next(),
+                PopupMode::Cycles => view.cycles.next(),
+                PopupMode::Confirm => {}
                 PopupMode::Help => self.help.next(),
             },
             _ => Err(DotViewerError::KeyError(KeyCode::Down))?,
@@ -0,0 +1,501 @@
+use crate::viewer::{
+    error::{DotViewerError, DotViewerResult},
+    modes::{Mode, PopupMode},
+    utils::config_dir,
+};
+
+use std::collections::HashMap;
+use std::fs;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Deserialize;
+
+/// `Action` is what a key sequence can be bound to in a keymap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum Action {
+    Quit,
+    FuzzySearch,
+    RegexSearch,
+    QuerySearch,
+    Command,
+    CloseTab,
+    NextTab,
+    PrevTab,
+    Left,
+    Down,
+    Up,
+    Right,
+    NextMatch,
+    PrevMatch,
+    First,
+    Last,
+    Enter,
+    JumpBack,
+    JumpForward,
+    SetMark,
+    GotoMark,
+    NextChange,
+    PrevChange,
+    ParentTab,
+    ToggleAttrs,
+    Yes,
+    No,
+    Delete,
+    Complete,
+    HistoryPrev,
+    HistoryNext,
+    HistorySearch,
+}
+
+/// Result of looking up a key sequence in a keymap.
+pub(crate) enum Lookup {
+    /// The sequence is bound to an action
+    Action(Action),
+    /// The sequence is a prefix of some bound sequences
+    Pending,
+    /// The sequence is not bound at all
+    None,
+}
+
+/// `Section` is a part of the keymap, applied in a `Mode` taking keybindings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Section {
+    Normal,
+    Tree,
+    Jumps,
+    Marks,
+    Lineage,
+    Stats,
+    Cycles,
+    Confirm,
+    Help,
+    Command,
+    Search,
+}
+
+const ACTIONS: &[(&str, Action)] = &[
+    ("quit", Action::Quit),
+    ("fuzzy_search", Action::FuzzySearch),
+    ("regex_search", Action::RegexSearch),
+    ("query_search", Action::QuerySearch),
+    ("command", Action::Command),
+    ("close_tab", Action::CloseTab),
+    ("next_tab", Action::NextTab),
+    ("prev_tab", Action::PrevTab),
+    ("parent_tab", Action::ParentTab),
+    ("left", Action::Left),
+    ("down", Action::Down),
+    ("up", Action::Up),
+    ("right", Action::Right),
+    ("next_match", Action::NextMatch),
+    ("prev_match", Action::PrevMatch),
+    ("first", Action::First),
+    ("last", Action::Last),
+    ("enter", Action::Enter),
+    ("jump_back", Action::JumpBack),
+    ("jump_forward", Action::JumpForward),
+    ("set_mark", Action::SetMark),
+    ("goto_mark", Action::GotoMark),
+    ("next_change", Action::NextChange),
+    ("prev_change", Action::PrevChange),
+    ("toggle_attrs", Action::ToggleAttrs),
+    ("yes", Action::Yes),
+    ("no", Action::No),
+    ("delete", Action::Delete),
+    ("complete", Action::Complete),
+    ("history_prev", Action::HistoryPrev),
+    ("history_next", Action::HistoryNext),
+    ("history_search", Action::HistorySearch),
+];
+
+const NORMAL: &[(Action, &[&str])] = &[
+    (Action::Quit, &["q"]),
+    (Action::FuzzySearch, &["/"]),
+    (Action::RegexSearch, &["r"]),
+    (Action::QuerySearch, &["?"]),
+    (Action::Command, &[":"]),
+    (Action::CloseTab, &["c"]),
+    (Action::NextTab, &["<Tab>"]),
+    (Action::PrevTab, &["<BackTab>"]),
+    (Action::ParentTab, &["gp"]),
+    (Action::Left, &["h", "<Left>"]),
+    (Action::Down, &["j", "<Down>"]),
+    (Action::Up, &["k", "<Up>"]),
+    (Action::Right, &["l", "<Right>"]),
+    (Action::NextMatch, &["n"]),
+    (Action::PrevMatch, &["N"]),
+    (Action::First, &["gg"]),
+    (Action::Last, &["G"]),
+    (Action::Enter, &["<CR>"]),
+    (Action::JumpBack, &["<C-o>"]),
+    (Action::JumpForward, &["<C-n>"]),
+    (Action::SetMark, &["m"]),
+    (Action::GotoMark, &["'"]),
+    (Action::NextChange, &["]c"]),
+    (Action::PrevChange, &["[c"]),
+    (Action::ToggleAttrs, &["t"]),
+];
+
+const TREE: &[(Action, &[&str])] = &[
+    (Action::Quit, &["q"]),
+    (Action::Left, &["h", "<Left>"]),
+    (Action::Down, &["j", "<Down>"]),
+    (Action::Up, &["k", "<Up>"]),
+    (Action::Right, &["l", "<Right>"]),
+    (Action::Enter, &["<CR>"]),
+];
+
+const JUMPS: &[(Action, &[&str])] = &[
+    (Action::Quit, &["q"]),
+    (Action::Down, &["j", "<Down>"]),
+    (Action::Up, &["k", "<Up>"]),
+    (Action::Enter, &["<CR>"]),
+];
+
+const MARKS: &[(Action, &[&str])] = &[
+    (Action::Quit, &["q"]),
+    (Action::Down, &["j", "<Down>"]),
+    (Action::Up, &["k", "<Up>"]),
+    (Action::Enter, &["<CR>"]),
+];
+
+const LINEAGE: &[(Action, &[&str])] = &[
+    (Action::Quit, &["q"]),
+    (Action::Down, &["j", "<Down>"]),
+    (Action::Up, &["k", "<Up>"]),
+    (Action::Enter, &["<CR>"]),
+];
+
+const STATS: &[(Action, &[&str])] = &[
+    (Action::Quit, &["q"]),
+    (Action::Down, &["j", "<Down>"]),
+    (Action::Up, &["k", "<Up>"]),
+    (Action::Enter, &["<CR>"]),
+];
+
+const CYCLES: &[(Action, &[&str])] = &[
+    (Action::Quit, &["q"]),
+    (Action::Down, &["j", "<Down>"]),
+    (Action::Up, &["k", "<Up>"]),
+    (Action::Enter, &["<CR>"]),
+];
+
+const CONFIRM: &[(Action, &[&str])] = &[(Action::Yes, &["y"]), (Action::No, &["n"])];
+
+const HELP: &[(Action, &[&str])] =
+    &[(Action::Quit, &["q"]), (Action::Down, &["j", "<Down>"]), (Action::Up, &["k", "<Up>"])];
+
+/// Keys editing the input form, in both command and search modes.
+const INPUT: &[(Action, &[&str])] = &[
+    (Action::Enter, &["<CR>"]),
+    (Action::Delete, &["<BS>"]),
+    (Action::Complete, &["<Tab>"]),
+    (Action::Left, &["<Left>"]),
+    (Action::Right, &["<Right>"]),
+    (Action::HistoryPrev, &["<Up>"]),
+    (Action::HistoryNext, &["<Down>"]),
+    (Action::HistorySearch, &["<C-r>"]),
+];
+
+/// Keymap file format, listing key sequences for each action to override, e.g.,
+///
+/// ```toml
+/// [normal]
+/// regex_search = ["s"]
+/// first = ["gg", "<Home>"]
+/// ```
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct Config {
+    normal: HashMap<String, Vec<String>>,
+    tree: HashMap<String, Vec<String>>,
+    jumps: HashMap<String, Vec<String>>,
+    marks: HashMap<String, Vec<String>>,
+    lineage: HashMap<String, Vec<String>>,
+    stats: HashMap<String, Vec<String>>,
+    cycles: HashMap<String, Vec<String>>,
+    confirm: HashMap<String, Vec<String>>,
+    help: HashMap<String, Vec<String>>,
+    command: HashMap<String, Vec<String>>,
+    search: HashMap<String, Vec<String>>,
+}
+
+/// `Keymap` maps key sequences to actions, for each mode taking keybindings.
+pub(crate) struct Keymap {
+    bindings: HashMap<Section, HashMap<Vec<String>, Action>>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::from_toml("").expect("default keymap should have no conflicts")
+    }
+}
+
+impl Keymap {
+    /// Load the keymap from `keys.toml` in the config directory, on top of the default keymap.
+    pub fn load() -> DotViewerResult<Self> {
+        match config_dir().map(|dir| dir.join("keys.toml")) {
+            Some(path) if path.exists() => {
+                let toml = fs::read_to_string(&path)?;
+
+                Self::from_toml(&toml)
+                    .map_err(|e| DotViewerError::KeymapError(format!("{}, {e}", path.display())))
+            }
+            _ => Ok(Self::default()),
+        }
+    }
+
+    fn from_toml(toml: &str) -> Result<Self, String> {
+        let config: Config = toml::from_str(toml).map_err(|e| e.to_string())?;
+
+        let sections = [
+            (Section::Normal, NORMAL, config.normal),
+            (Section::Tree, TREE, config.tree),
+            (Section::Jumps, JUMPS, config.jumps),
+            (Section::Marks, MARKS, config.marks),
+            (Section::Lineage, LINEAGE, config.lineage),
+            (Section::Stats, STATS, config.stats),
+            (Section::Cycles, CYCLES, config.cycles),
+            (Section::Confirm, CONFIRM, config.confirm),
+            (Section::Help, HELP, config.help),
+            (Section::Command, INPUT, config.command),
+            (Section::Search, INPUT, config.search),
+        ];
+
+        let mut bindings = HashMap::new();
+        let mut conflicts = Vec::new();
+
+        for (section, defaults, overrides) in sections {
+            let mut actions: HashMap<Action, Vec<String>> = (defaults.iter())
+                .map(|(action, seqs)| (*action, seqs.iter().map(|s| s.to_string()).collect()))
+                .collect();
+
+            for (name, seqs) in overrides {
+                let action = (ACTIONS.iter())
+                    .find(|(n, _)| *n == name)
+                    .map(|(_, action)| *action)
+                    .ok_or(format!("no such action {name:?}"))?;
+
+                actions.insert(action, seqs);
+            }
+
+            let mut seqs = Vec::new();
+            for (action, keys) in actions {
+                for key in keys {
+                    let seq = parse(&key).ok_or(format!("invalid key sequence {key:?}"))?;
+                    if section.is_input() && seq.len() > 1 {
+                        return Err(format!(
+                            "{key:?} is not a single key, as needed in {}",
+                            section.name()
+                        ));
+                    }
+                    seqs.push((seq, key, action));
+                }
+            }
+            seqs.sort_by(|a, b| a.1.cmp(&b.1));
+
+            for (i, (seq_a, key_a, action_a)) in seqs.iter().enumerate() {
+                for (seq_b, key_b, action_b) in &seqs[i + 1..] {
+                    let name_a = name(*action_a);
+                    let name_b = name(*action_b);
+
+                    if seq_a == seq_b {
+                        conflicts.push(format!(
+                            "{key_a:?} is bound to both {name_a} and {name_b} in {}",
+                            section.name()
+                        ));
+                    } else if seq_b.starts_with(seq_a) {
+                        conflicts.push(format!(
+                            "{key_a:?} ({name_a}) shadows {key_b:?} ({name_b}) in {}",
+                            section.name()
+                        ));
+                    } else if seq_a.starts_with(seq_b) {
+                        conflicts.push(format!(
+                            "{key_b:?} ({name_b}) shadows {key_a:?} ({name_a}) in {}",
+                            section.name()
+                        ));
+                    }
+                }
+            }
+
+            let seqs: HashMap<Vec<String>, Action> =
+                seqs.into_iter().map(|(seq, _, action)| (seq, action)).collect();
+            bindings.insert(section, seqs);
+        }
+
+        if !conflicts.is_empty() {
+            return Err(conflicts.join(", "));
+        }
+
+        Ok(Self { bindings })
+    }
+
+    /// Look up the key sequence `keys` typed in `mode`.
+    pub fn lookup(&self, mode: &Mode, keys: &[String]) -> Lookup {
+        let Some(bindings) = self.bindings.get(&Section::of(mode)) else {
+            return Lookup::None;
+        };
+
+        if let Some(action) = bindings.get(keys) {
+            Lookup::Action(*action)
+        } else if bindings.keys().any(|seq| seq.starts_with(keys)) {
+            Lookup::Pending
+        } else {
+            Lookup::None
+        }
+    }
+}
+
+impl Section {
+    fn of(mode: &Mode) -> Self {
+        match mode {
+            Mode::Normal => Self::Normal,
+            Mode::Popup(PopupMode::Tree) => Self::Tree,
+            Mode::Popup(PopupMode::Jumps) => Self::Jumps,
+            Mode::Popup(PopupMode::Marks) => Self::Marks,
+            Mode::Popup(PopupMode::Lineage) => Self::Lineage,
+            Mode::Popup(PopupMode::Stats) => Self::Stats,
+            Mode::Popup(PopupMode::Cycles) => Self::Cycles,
+            Mode::Popup(PopupMode::Confirm) => Self::Confirm,
+            Mode::Popup(PopupMode::Help) => Self::Help,
+            Mode::Command => Self::Command,
+            Mode::Search(_) => Self::Search,
+        }
+    }
+
+    /// Whether the section applies while typing in the input form,
+    /// where unbound keys are typed in and so only single keys can be bound.
+    fn is_input(&self) -> bool {
+        matches!(self, Self::Command | Self::Search)
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Normal => "normal",
+            Self::Tree => "tree",
+            Self::Jumps => "jumps",
+            Self::Marks => "marks",
+            Self::Lineage => "lineage",
+            Self::Stats => "stats",
+            Self::Cycles => "cycles",
+            Self::Confirm => "confirm",
+            Self::Help => "help",
+            Self::Command => "command",
+            Self::Search => "search",
+        }
+    }
+}
+
+/// Represent a key event as a token of a key sequence, e.g., `g`, `<CR>` or `<C-o>`.
+pub(crate) fn token(key: &KeyEvent) -> String {
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+    let alt = key.modifiers.contains(KeyModifiers::ALT);
+
+    render(key.code, ctrl, alt)
+}
+
+fn name(action: Action) -> &'static str {
+    ACTIONS.iter().find(|(_, a)| *a == action).map(|(name, _)| *name).unwrap()
+}
+
+fn render(code: KeyCode, ctrl: bool, alt: bool) -> String {
+    let name = match code {
+        KeyCode::Char(c) if !ctrl && !alt && c != '<' && c != ' ' => return c.to_string(),
+        KeyCode::Char('<') => "lt".to_string(),
+        KeyCode::Char(' ') => "Space".to_string(),
+        KeyCode::Char(c) if ctrl => c.to_ascii_lowercase().to_string(),
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::Enter => "CR".to_string(),
+        KeyCode::Tab => "Tab".to_string(),
+        KeyCode::BackTab => "BackTab".to_string(),
+        KeyCode::Backspace => "BS".to_string(),
+        KeyCode::Esc => "Esc".to_string(),
+        KeyCode::Up => "Up".to_string(),
+        KeyCode::Down => "Down".to_string(),
+        KeyCode::Left => "Left".to_string(),
+        KeyCode::Right => "Right".to_string(),
+        KeyCode::Home => "Home".to_string(),
+        KeyCode::End => "End".to_string(),
+        KeyCode::PageUp => "PageUp".to_string(),
+        KeyCode::PageDown => "PageDown".to_string(),
+        KeyCode::Delete => "Del".to_string(),
+        KeyCode::Insert => "Insert".to_string(),
+        KeyCode::F(n) => format!("F{n}"),
+        _ => "Nul".to_string(),
+    };
+
+    let ctrl = if ctrl { "C-" } else { "" };
+    let alt = if alt { "A-" } else { "" };
+
+    format!("<{ctrl}{alt}{name}>")
+}
+
+/// Parse a key sequence written as in Vim, e.g., `gg` or `<C-o>`, into tokens.
+fn parse(seq: &str) -> Option<Vec<String>> {
+    let mut tokens = Vec::new();
+
+    let mut chars = seq.chars();
+    while let Some(c) = chars.next() {
+        if c != '<' {
+            tokens.push(render(KeyCode::Char(c), false, false));
+            continue;
+        }
+
+        let mut inner = String::new();
+        loop {
+            match chars.next()? {
+                '>' if !inner.is_empty() => break,
+                c => inner.push(c),
+            }
+        }
+
+        tokens.push(parse_special(&inner)?);
+    }
+
+    (!tokens.is_empty()).then_some(tokens)
+}
+
+fn parse_special(inner: &str) -> Option<String> {
+    let mut ctrl = false;
+    let mut alt = false;
+
+    let mut inner = inner;
+    while inner.len() > 2 {
+        match inner.get(..2) {
+            Some("C-" | "c-") => ctrl = true,
+            Some("A-" | "a-" | "M-" | "m-") => alt = true,
+            _ => break,
+        }
+        inner = &inner[2..];
+    }
+
+    let mut chars = inner.chars();
+    let code = match (chars.next(), chars.next()) {
+        (Some(c), None) => KeyCode::Char(c),
+        _ => match inner.to_lowercase().as_str() {
+            "cr" | "enter" | "return" => KeyCode::Enter,
+            "tab" => KeyCode::Tab,
+            "backtab" | "s-tab" => KeyCode::BackTab,
+            "bs" | "backspace" => KeyCode::Backspace,
+            "esc" => KeyCode::Esc,
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            "del" | "delete" => KeyCode::Delete,
+            "insert" => KeyCode::Insert,
+            "space" => KeyCode::Char(' '),
+            "lt" => KeyCode::Char('<'),
+            name => {
+                let n = name.strip_prefix('f')?.parse().ok()?;
+                KeyCode::F(n)
+            }
+        },
+    };
+
+    Some(render(code, ctrl, alt))
+}
@@ -0,0 +1,200 @@
+use crate::viewer::{
+    error::{DotViewerError, DotViewerResult},
+    utils::config_dir,
+};
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::env;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{self, Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use log::warn;
+use serde::Deserialize;
+
+/// How long to wait for the viewer to fail, before taking it as launched.
+const GRACE: Duration = Duration::from_millis(500);
+
+/// Config file format, e.g.,
+///
+/// ```toml
+/// viewer = "gvedit {file}"
+/// ```
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct Config {
+    viewer: Option<String>,
+}
+
+/// `Launcher` launches an external viewer on dot files,
+/// given a command with a `{file}` placeholder, e.g., `xdot {file}`.
+///
+/// Temporary files written for the viewer are removed when the launcher is dropped on exit.
+pub(crate) struct Launcher {
+    pub command: String,
+    temps: HashSet<PathBuf>,
+}
+
+impl Default for Launcher {
+    fn default() -> Self {
+        Self::new("xdot {file}".to_string())
+    }
+}
+
+impl Drop for Launcher {
+    fn drop(&mut self) {
+        for temp in &self.temps {
+            let _ = fs::remove_file(temp);
+        }
+    }
+}
+
+impl Launcher {
+    /// Load the viewer command from `command` if given (e.g., by `--viewer`),
+    /// or else from `config.toml` in the config directory, if any.
+    pub fn load(command: Option<String>) -> DotViewerResult<Self> {
+        if let Some(command) = command {
+            return Ok(Self::new(command));
+        }
+
+        let path = match config_dir().map(|dir| dir.join("config.toml")) {
+            Some(path) if path.exists() => path,
+            _ => return Ok(Self::default()),
+        };
+
+        let toml = fs::read_to_string(&path)?;
+        let config: Config = toml::from_str(&toml)
+            .map_err(|e| DotViewerError::ConfigError(format!("{}, {e}", path.display())))?;
+
+        Ok(config.viewer.map_or_else(Self::default, Self::new))
+    }
+
+    fn new(command: String) -> Self {
+        Self { command, temps: HashSet::new() }
+    }
+
+    /// Temporary file to view the tab titled `title` in, one per tab and process,
+    /// named by a hash of the title since titles grow long.
+    pub fn temp(&mut self, title: &str) -> PathBuf {
+        let mut hasher = DefaultHasher::new();
+        title.hash(&mut hasher);
+        let name = format!("dot-viewer-{}-{:016x}.dot", process::id(), hasher.finish());
+
+        let temp = env::temp_dir().join(name);
+        self.temps.insert(temp.clone());
+
+        temp
+    }
+
+    /// Launch the viewer on the dot file at `path`, appending it if there is no placeholder.
+    /// Returns the name of the program launched.
+    ///
+    /// The viewer keeps running in the background, but if it exits with an error right away,
+    /// what it wrote to stderr is reported.
+    pub fn launch(&self, path: &Path) -> DotViewerResult<String> {
+        let file = path.to_string_lossy();
+
+        let mut words = split(&self.command).ok_or_else(|| {
+            DotViewerError::LaunchError(format!("{}, unterminated quote", self.command))
+        })?;
+        if !self.command.contains("{file}") {
+            words.push("{file}".to_string());
+        }
+        let words: Vec<String> = words.iter().map(|word| word.replace("{file}", &file)).collect();
+
+        let (program, args) = (words.split_first())
+            .ok_or(DotViewerError::LaunchError("no viewer command configured".to_string()))?;
+
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| DotViewerError::LaunchError(format!("{program}, {e}")))?;
+
+        let start = Instant::now();
+        while start.elapsed() < GRACE {
+            if let Some(status) = child.try_wait()? {
+                if status.success() {
+                    break;
+                }
+
+                let mut stderr = String::new();
+                if let Some(mut pipe) = child.stderr.take() {
+                    pipe.read_to_string(&mut stderr)?;
+                }
+                let stderr = stderr.trim();
+                let reason =
+                    if stderr.is_empty() { status.to_string() } else { stderr.to_string() };
+
+                return Err(DotViewerError::LaunchError(format!("{program}, {reason}")));
+            }
+
+            thread::sleep(Duration::from_millis(20));
+        }
+
+        // keep draining stderr into the log, so that the viewer never blocks on a full pipe
+        if let Some(mut pipe) = child.stderr.take() {
+            let program = program.clone();
+            thread::spawn(move || {
+                let mut stderr = String::new();
+                if pipe.read_to_string(&mut stderr).is_ok() && !stderr.trim().is_empty() {
+                    warn!("{program}: {}", stderr.trim());
+                }
+            });
+        }
+
+        Ok(program.clone())
+    }
+}
+
+/// Split `command` into words as a shell does, honoring single and double quotes and backslash
+/// escapes, e.g., `"/opt/my viewer/bin/view" --title 'a b' {file}`.
+/// Returns `None` on an unterminated quote.
+fn split(command: &str) -> Option<Vec<String>> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+
+    let mut chars = command.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => words.extend(word.take()),
+            '\'' => {
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next()? {
+                        '\'' => break,
+                        c => word.push(c),
+                    }
+                }
+            }
+            '"' => {
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next()? {
+                        '"' => break,
+                        '\\' => match chars.next()? {
+                            c @ ('"' | '\\' | '$' | '`') => word.push(c),
+                            c => {
+                                word.push('\\');
+                                word.push(c);
+                            }
+                        },
+                        c => word.push(c),
+                    }
+                }
+            }
+            '\\' => word.get_or_insert_with(String::new).push(chars.next()?),
+            c => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    words.extend(word);
+
+    Some(words)
+}
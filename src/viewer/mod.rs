@@ -1,15 +1,26 @@
 mod app;
 mod command;
+mod confirm;
+mod diff;
 mod error;
 mod help;
 mod keybindings;
+mod keymap;
+mod launcher;
 mod modes;
+mod mouse;
+mod recipe;
+mod session;
 mod success;
 mod utils;
 mod view;
 
 pub(crate) use crate::viewer::{
     app::App,
+    diff::{Attrs, Change, Diff},
+    keymap::Keymap,
+    launcher::Launcher,
     modes::{Mode, PopupMode, SearchMode},
-    view::{Focus, View},
+    utils::{state_dir, Critical, Format, Query, Stats},
+    view::{AttrsMode, Focus, View},
 };
@@ -1,3 +1,5 @@
+use serde::{Deserialize, Serialize};
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 /// `Mode` represents the context that the application, `dot-viewer` is in.
 pub(crate) enum Mode {
@@ -9,18 +11,32 @@ pub(crate) enum Mode {
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 /// In `PopupMode`, users can
-/// - navigate the subgraphs, or
+/// - navigate the subgraphs,
+/// - navigate the jump list,
+/// - navigate the marks,
+/// - navigate the lineage of tabs,
+/// - navigate the statistics of the view,
+/// - navigate the cycles of the view,
+/// - confirm an action, or
 /// - see help message.
 pub(crate) enum PopupMode {
     Tree,
+    Jumps,
+    Marks,
+    Lineage,
+    Stats,
+    Cycles,
+    Confirm,
     Help,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 /// In `SearchMode`, users can search for a node with,
-/// - fuzzy search against node ids, or
-/// - regex search against raw node representation in dot format.
+/// - fuzzy search against node ids,
+/// - regex search against raw node representation in dot format, or
+/// - query search with predicates on node attributes and degrees.
 pub(crate) enum SearchMode {
     Fuzzy,
     Regex,
+    Query,
 }
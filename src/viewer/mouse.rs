@@ -0,0 +1,121 @@
+use crate::viewer::{
+    app::App,
+    error::DotViewerResult,
+    modes::Mode,
+    success::Success,
+    view::{Focus, View},
+};
+
+use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
+use log::warn;
+use tui::layout::Rect;
+
+impl App {
+    pub fn mouse(&mut self, mouse: MouseEvent) {
+        let (x, y) = (mouse.column, mouse.row);
+
+        let result = match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) => self.click(x, y),
+            MouseEventKind::ScrollUp => self.scroll(x, y, true),
+            MouseEventKind::ScrollDown => self.scroll(x, y, false),
+            _ => return,
+        };
+
+        self.result = result.map(|_| Success::default());
+
+        if let Err(err) = &self.result {
+            warn!("{err}");
+        }
+    }
+
+    fn click(&mut self, x: u16, y: u16) -> DotViewerResult<()> {
+        if self.mode != Mode::Normal {
+            return Ok(());
+        }
+
+        if let Some(idx) = self.tab_at(x, y) {
+            self.tabs.select(idx);
+            return Ok(());
+        }
+
+        let view = self.tabs.selected();
+        view.click(x, y)
+    }
+
+    fn scroll(&mut self, x: u16, y: u16, up: bool) -> DotViewerResult<()> {
+        match &self.mode {
+            Mode::Normal => {
+                let view = self.tabs.selected();
+                view.scroll(x, y, up)
+            }
+            Mode::Popup(_) if up => self.up(),
+            Mode::Popup(_) => self.down(),
+            _ => Ok(()),
+        }
+    }
+
+    /// Index of the tab whose title is drawn at the position `(x, y)`, if any.
+    /// Titles are laid out as `tui` does, each padded by a space and separated by a divider.
+    fn tab_at(&self, x: u16, y: u16) -> Option<usize> {
+        let area = self.tabs.area;
+        if y != area.top() || x < area.left() || x >= area.right() {
+            return None;
+        }
+
+        let mut left = area.left();
+        for (idx, view) in self.tabs.tabs.iter().enumerate() {
+            let width = view.title.chars().count() as u16;
+            let right = left + width + 2;
+
+            if x < right {
+                return Some(idx);
+            }
+
+            left = right + 1;
+        }
+
+        None
+    }
+}
+
+impl View {
+    fn click(&mut self, x: u16, y: u16) -> DotViewerResult<()> {
+        if let Some(idx) = self.current.at(x, y) {
+            self.focus = Focus::Current;
+            self.current.select(idx);
+            self.update_adjacent()
+        } else if let Some(idx) = self.prevs.at(x, y) {
+            self.focus = Focus::Prev;
+            self.prevs.select(idx);
+            self.goto_adjacent()
+        } else if let Some(idx) = self.nexts.at(x, y) {
+            self.focus = Focus::Next;
+            self.nexts.select(idx);
+            self.goto_adjacent()
+        } else {
+            Ok(())
+        }
+    }
+
+    fn scroll(&mut self, x: u16, y: u16, up: bool) -> DotViewerResult<()> {
+        let within = |area: Rect| {
+            area.left() <= x && x < area.right() && area.top() <= y && y < area.bottom()
+        };
+
+        if within(self.current.area) {
+            self.focus = Focus::Current;
+        } else if within(self.prevs.area) {
+            self.focus = Focus::Prev;
+        } else if within(self.nexts.area) {
+            self.focus = Focus::Next;
+        } else {
+            return Ok(());
+        }
+
+        if up {
+            self.up()
+        } else {
+            self.down()
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use crate::viewer::{
+    error::{DotViewerError, DotViewerResult},
+    modes::SearchMode,
+    utils::Direction,
+    view::View,
+};
+
+use serde::{Deserialize, Serialize};
+
+/// `Step` is how a view was derived from its parent view.
+///
+/// A view holds the steps taken from the root view as its recipe,
+/// which is replayed to rebuild the view on a newly parsed graph.
+/// A view combined from two views starts its recipe from the root view,
+/// with the recipes of both views.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub(crate) enum Step {
+    Filter { smode: SearchMode, key: String },
+    Neighbors { id: String, depth: usize },
+    Cone { seeds: Vec<String>, depth: Option<usize>, direction: Direction },
+    Subgraph { id: String },
+    Cycle { id: String },
+    Critical { attr: Option<String> },
+    Path { from: String, to: String, shortest: bool },
+    Combine { op: SetOp, lhs: Vec<Step>, rhs: Vec<Step> },
+}
+
+/// `SetOp` is a set operation on the nodes of two views.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub(crate) enum SetOp {
+    Union,
+    Intersect,
+    Diff,
+}
+
+impl SetOp {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Union => "union",
+            Self::Intersect => "intersect",
+            Self::Diff => "diff",
+        }
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Self::Union => "|",
+            Self::Intersect => "&",
+            Self::Diff => "\\",
+        }
+    }
+}
+
+impl View {
+    /// Rebuild a view by replaying `steps` from this view.
+    pub fn replay(&mut self, steps: &[Step]) -> DotViewerResult<View> {
+        let (step, steps) = (steps.split_first())
+            .ok_or(DotViewerError::ViewerError("nothing to replay".to_string()))?;
+
+        let mut view = self.apply(step)?;
+        for step in steps {
+            view = view.apply(step)?;
+        }
+
+        Ok(view)
+    }
+
+    fn apply(&mut self, step: &Step) -> DotViewerResult<View> {
+        match step {
+            Step::Filter { smode, key } => {
+                self.update_search(smode, key);
+                self.filter()
+            }
+            Step::Neighbors { id, depth } => {
+                self.select(id)?;
+                self.neighbors(*depth)
+            }
+            Step::Cone { seeds, depth, direction } => self.cone(seeds, *depth, *direction),
+            Step::Subgraph { id } => self.subgraph_of(id),
+            Step::Cycle { id } => self.cycle_of(id),
+            Step::Critical { attr } => self.critical(attr.as_deref()),
+            Step::Path { from, to, shortest } => self.path(from, to, *shortest),
+            Step::Combine { op, lhs, rhs } => {
+                // an empty recipe stands for the root view, which is this view
+                let lhs = (!lhs.is_empty()).then(|| self.replay(lhs)).transpose()?;
+                let rhs = (!rhs.is_empty()).then(|| self.replay(rhs)).transpose()?;
+
+                let root = &*self;
+                let lhs = lhs.as_ref().unwrap_or(root);
+                let rhs = rhs.as_ref().unwrap_or(root);
+
+                lhs.combine(rhs, *op, root)
+            }
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use crate::viewer::{
+    error::{DotViewerError, DotViewerResult},
+    modes::SearchMode,
+    recipe::Step,
+    utils::List,
+    view::{Focus, View},
+};
+
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+/// `Session` is a snapshot of the open tabs and marks, saved by `:mksession` as JSON
+/// and restored by `--session`.
+///
+/// Each tab is saved as its recipe from the root view, rather than the graph itself,
+/// so a session stays small and follows changes to the dot file.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct Session {
+    /// Path to the dot file that the session was made on
+    pub path: String,
+    /// Path to the dot file that it was compared against, if any
+    #[serde(default)]
+    pub before: Option<String>,
+    /// Open tabs, from the root view
+    pub tabs: Vec<Tab>,
+    /// Index of the selected tab
+    pub selected: usize,
+    /// Named marks on nodes
+    pub marks: Vec<(char, String)>,
+}
+
+/// `Tab` is a snapshot of a view in a session.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct Tab {
+    /// Title of the view, to report the tab if it cannot be rebuilt
+    pub title: String,
+    /// Id of the tab and of its parent tab
+    pub id: usize,
+    pub parent: Option<usize>,
+    /// Steps taken to derive the view from the root view, empty for the root view
+    pub recipe: Vec<Step>,
+    /// Id of the selected node
+    pub node: String,
+    pub focus: Focus,
+    pub smode: SearchMode,
+    pub key: String,
+}
+
+impl Session {
+    /// Load a session from the JSON file at `path`.
+    pub fn load(path: &str) -> DotViewerResult<Self> {
+        let json = fs::read_to_string(path)?;
+
+        serde_json::from_str(&json)
+            .map_err(|e| DotViewerError::SessionError(format!("{path}, {e}")))
+    }
+
+    /// Save the session to the JSON file at `path`.
+    pub fn save(&self, path: &str) -> DotViewerResult<()> {
+        if let Some(dir) = Path::new(path).parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| DotViewerError::SessionError(format!("{path}, {e}")))?;
+        fs::write(path, json)?;
+
+        Ok(())
+    }
+}
+
+impl View {
+    /// Take a snapshot of the view for a session.
+    pub fn snapshot(&self) -> Tab {
+        Tab {
+            title: self.title.clone(),
+            id: self.id,
+            parent: self.parent,
+            recipe: self.recipe.clone(),
+            node: self.current_id(),
+            focus: self.focus.clone(),
+            smode: self.smode.clone(),
+            key: self.key.clone(),
+        }
+    }
+
+    /// Restore the tab lineage, selected node, focus and search of `tab`,
+    /// which this view is rebuilt from.
+    pub fn resume(&mut self, tab: Tab) {
+        self.id = tab.id;
+        self.parent = tab.parent;
+
+        if tab.key.is_empty() {
+            self.key.clear();
+            self.matches = List::from_iter(Vec::new());
+        } else {
+            self.update_search(&tab.smode, &tab.key);
+        }
+
+        self.focus = tab.focus;
+
+        // stay on the first node if the selected node vanished
+        let _ = self.select(&tab.node);
+    }
+}
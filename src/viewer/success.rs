@@ -4,7 +4,11 @@ use std::fmt;
 #[allow(clippy::enum_variant_names)]
 pub(crate) enum Success {
     ExportSuccess(String),
-    XdotSuccess,
+    RenderSuccess(String),
+    LaunchSuccess(String),
+    SessionSuccess(String),
+    RestoreSuccess { dropped: Vec<String> },
+    ReloadSuccess { vanished: Vec<String>, dropped: Vec<String> },
     Silent,
 }
 
@@ -18,8 +22,39 @@ impl fmt::Display for Success {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match &self {
             Self::ExportSuccess(filename) => write!(f, "successfully exported to {filename}"),
-            Self::XdotSuccess => write!(f, "launched xdot"),
+            Self::RenderSuccess(filename) => write!(f, "successfully rendered to {filename}"),
+            Self::LaunchSuccess(program) => write!(f, "launched {program}"),
+            Self::SessionSuccess(filename) => write!(f, "successfully saved session to {filename}"),
+            Self::RestoreSuccess { dropped } => {
+                write!(f, "restored session")?;
+                if !dropped.is_empty() {
+                    write!(f, ", dropped tabs: {}", preview(dropped))?;
+                }
+                Ok(())
+            }
+            Self::ReloadSuccess { vanished, dropped } => {
+                write!(f, "reloaded graph")?;
+                if !vanished.is_empty() {
+                    write!(f, ", {} nodes vanished: {}", vanished.len(), preview(vanished))?;
+                }
+                if !dropped.is_empty() {
+                    write!(f, ", dropped tabs: {}", preview(dropped))?;
+                }
+                Ok(())
+            }
             Self::Silent => Ok(()),
         }
     }
 }
+
+/// Join the first few `items` for the result line.
+fn preview(items: &[String]) -> String {
+    const MAX: usize = 5;
+
+    let mut preview = items.iter().take(MAX).cloned().collect::<Vec<_>>().join(", ");
+    if items.len() > MAX {
+        preview.push_str(", ...");
+    }
+
+    preview
+}
@@ -0,0 +1,103 @@
+use crate::viewer::{
+    error::{DotViewerError, DotViewerResult},
+    utils::number,
+};
+
+use std::collections::{HashMap, HashSet};
+
+use dot_graph::Graph;
+
+/// `Critical` holds the longest weighted path through a graph, and the slack of each node,
+/// i.e., how much heavier the node could get without lengthening the path.
+///
+/// Without an attribute, every node weighs 1.
+/// With an attribute, nodes and edges weigh its numeric value, or 0 if they do not have it.
+#[derive(Debug, Clone)]
+pub(crate) struct Critical {
+    /// Attribute that weights come from, if any
+    pub attr: Option<String>,
+    /// Nodes on the critical path, from its start
+    pub path: Vec<String>,
+    /// Nodes on the critical path, for lookup
+    pub nodes: HashSet<String>,
+    /// Total weight of the critical path
+    pub total: f64,
+    /// Slack of each node
+    pub slack: HashMap<String, f64>,
+}
+
+impl Critical {
+    /// Compute the critical path of `graph`, given its `nodes` in topological order,
+    /// with weights from `attr` if given.
+    pub fn new(graph: &Graph, nodes: &[String], attr: Option<&str>) -> DotViewerResult<Self> {
+        let node_weight = |id: &String| -> f64 {
+            match attr {
+                Some(attr) => (graph.search_node(id))
+                    .and_then(|node| node.attrs().get(attr).and_then(|value| number(value)))
+                    .unwrap_or(0.0),
+                None => 1.0,
+            }
+        };
+
+        // parallel edges weigh as much as the heaviest of them
+        let mut edge_weights: HashMap<(&String, &String), f64> = HashMap::new();
+        if let Some(attr) = attr {
+            for edge in graph.collect_edges(graph.id())? {
+                let weight = edge.attrs().get(attr).and_then(|value| number(value)).unwrap_or(0.0);
+                let entry = edge_weights.entry((edge.from(), edge.to())).or_insert(weight);
+                *entry = entry.max(weight);
+            }
+        }
+        let edge_weight = |from: &String, to: &String| -> f64 {
+            edge_weights.get(&(from, to)).copied().unwrap_or(0.0)
+        };
+
+        // weight of the heaviest path ending at each node, and the previous node on it
+        let mut finish: HashMap<&String, (f64, Option<&String>)> = HashMap::new();
+        for id in nodes {
+            let mut best = (0.0, None);
+            for from in graph.froms(id)? {
+                let Some(&(weight, _)) = finish.get(from) else {
+                    return Err(DotViewerError::ViewerError(
+                        "critical path needs an acyclic graph".to_string(),
+                    ));
+                };
+
+                let weight = weight + edge_weight(from, id);
+                if best.1.is_none() || weight > best.0 {
+                    best = (weight, Some(from));
+                }
+            }
+            finish.insert(id, (best.0 + node_weight(id), best.1));
+        }
+
+        let (&last, &(total, _)) = (finish.iter())
+            .max_by(|(a, (x, _)), (b, (y, _))| x.total_cmp(y).then(b.cmp(a)))
+            .expect("there is always a node in a view");
+
+        let mut path = vec![last.clone()];
+        let mut last = last;
+        while let Some(&(_, Some(from))) = finish.get(last) {
+            path.push(from.clone());
+            last = from;
+        }
+        path.reverse();
+
+        // latest time each node may finish without lengthening the critical path
+        let mut latest: HashMap<&String, f64> = HashMap::new();
+        for id in nodes.iter().rev() {
+            let mut bound = total;
+            for to in graph.tos(id)? {
+                let start = latest[to] - node_weight(to) - edge_weight(id, to);
+                bound = bound.min(start);
+            }
+            latest.insert(id, bound);
+        }
+
+        let slack = (nodes.iter()).map(|id| (id.clone(), latest[id] - finish[id].0)).collect();
+
+        let nodes = path.iter().cloned().collect();
+
+        Ok(Self { attr: attr.map(String::from), path, nodes, total, slack })
+    }
+}
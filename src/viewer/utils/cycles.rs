@@ -0,0 +1,160 @@
+use crate::viewer::error::DotViewerResult;
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use dot_graph::Graph;
+
+/// `Cycle` is a strongly connected component of a graph that contains a cycle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Cycle {
+    /// Nodes in the component, sorted by id
+    pub nodes: Vec<String>,
+    /// One of the shortest cycles through the first node, ending where it starts
+    pub path: Vec<String>,
+}
+
+/// Order nodes of a possibly cyclic `graph` by its condensation, the DAG of its strongly
+/// connected components, in topological order, with nodes in each component sorted by id.
+/// Also returns the components containing cycles.
+pub(crate) fn condensation(graph: &Graph) -> DotViewerResult<(Vec<String>, Vec<Cycle>)> {
+    let mut nodes: Vec<&String> =
+        (graph.collect_nodes(graph.id())?.into_iter()).map(|node| node.id()).collect();
+    nodes.sort_unstable();
+
+    let mut components = tarjan(graph, &nodes)?;
+    // tarjan finds components in reverse topological order
+    components.reverse();
+
+    let mut order = Vec::with_capacity(nodes.len());
+    let mut cycles = Vec::new();
+
+    for mut component in components {
+        component.sort_unstable();
+
+        let first = component[0].clone();
+        let on_cycle =
+            component.len() > 1 || graph.tos(&first)?.iter().any(|to| to.as_str() == first);
+        if on_cycle {
+            let path = cycle_through(graph, &component)?;
+            cycles.push(Cycle { nodes: component.clone(), path });
+        }
+
+        order.extend(component);
+    }
+
+    Ok((order, cycles))
+}
+
+/// Find strongly connected components with Tarjan's algorithm, without recursion.
+fn tarjan(graph: &Graph, nodes: &[&String]) -> DotViewerResult<Vec<Vec<String>>> {
+    let mut counter = 0;
+    let mut indices: HashMap<&String, usize> = HashMap::new();
+    let mut lowlinks: HashMap<&String, usize> = HashMap::new();
+    let mut stack: Vec<&String> = Vec::new();
+    let mut on_stack: HashSet<&String> = HashSet::new();
+    let mut components = Vec::new();
+
+    for &root in nodes {
+        if indices.contains_key(root) {
+            continue;
+        }
+
+        // frames of the depth-first search, each with its successors and the next one to visit
+        let mut frames: Vec<(&String, Vec<&String>, usize)> = Vec::new();
+
+        indices.insert(root, counter);
+        lowlinks.insert(root, counter);
+        counter += 1;
+        stack.push(root);
+        on_stack.insert(root);
+        frames.push((root, successors(graph, root)?, 0));
+
+        while let Some(frame) = frames.last_mut() {
+            let id = frame.0;
+
+            if frame.2 < frame.1.len() {
+                let to = frame.1[frame.2];
+                frame.2 += 1;
+
+                if !indices.contains_key(to) {
+                    indices.insert(to, counter);
+                    lowlinks.insert(to, counter);
+                    counter += 1;
+                    stack.push(to);
+                    on_stack.insert(to);
+                    frames.push((to, successors(graph, to)?, 0));
+                } else if on_stack.contains(to) {
+                    let lowlink = lowlinks[id].min(indices[to]);
+                    lowlinks.insert(id, lowlink);
+                }
+
+                continue;
+            }
+
+            frames.pop();
+            if let Some(&(parent, _, _)) = frames.last() {
+                let lowlink = lowlinks[parent].min(lowlinks[id]);
+                lowlinks.insert(parent, lowlink);
+            }
+
+            if lowlinks[id] == indices[id] {
+                let mut component = Vec::new();
+                while let Some(member) = stack.pop() {
+                    on_stack.remove(member);
+                    component.push(member.clone());
+                    if member == id {
+                        break;
+                    }
+                }
+                components.push(component);
+            }
+        }
+    }
+
+    Ok(components)
+}
+
+/// Collect successors of `id`, sorted so that the components are found deterministically.
+fn successors<'a>(graph: &'a Graph, id: &String) -> DotViewerResult<Vec<&'a String>> {
+    let mut tos = Vec::from_iter(graph.tos(id)?);
+    tos.sort_unstable();
+
+    Ok(tos)
+}
+
+/// Find one of the shortest cycles through the first node of a strongly connected `component`,
+/// by a breadth-first search within the component, up to the first node with an edge back.
+fn cycle_through(graph: &Graph, component: &[String]) -> DotViewerResult<Vec<String>> {
+    let first = &component[0];
+    let members: HashSet<&String> = component.iter().collect();
+
+    let mut parents: HashMap<&String, &String> = HashMap::new();
+    let mut queue = VecDeque::from([first]);
+
+    while let Some(id) = queue.pop_front() {
+        let tos = successors(graph, id)?;
+
+        if tos.contains(&first) {
+            let mut path = vec![id.clone()];
+            let mut id = id;
+            while let Some(&parent) = parents.get(id) {
+                path.push(parent.clone());
+                id = parent;
+            }
+            path.reverse();
+            path.push(first.clone());
+
+            return Ok(path);
+        }
+
+        for to in tos {
+            if to != first && members.contains(to) && !parents.contains_key(to) {
+                parents.insert(to, id);
+                queue.push_back(to);
+            }
+        }
+    }
+
+    // unreachable for a strongly connected component, which has a cycle through every node
+    Ok(vec![first.clone(), first.clone()])
+}
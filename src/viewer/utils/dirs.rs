@@ -0,0 +1,29 @@
+use std::env;
+use std::path::PathBuf;
+
+/// Directory holding user configurations, i.e., `$XDG_CONFIG_HOME/dot-viewer`
+/// or `~/.config/dot-viewer` by default.
+pub(crate) fn config_dir() -> Option<PathBuf> {
+    xdg_dir("XDG_CONFIG_HOME", ".config")
+}
+
+/// Directory holding states persisted across sessions, i.e., `$XDG_STATE_HOME/dot-viewer`
+/// or `~/.local/state/dot-viewer` by default.
+pub(crate) fn state_dir() -> Option<PathBuf> {
+    xdg_dir("XDG_STATE_HOME", ".local/state")
+}
+
+/// Directory holding user data such as exported files, i.e., `$XDG_DATA_HOME/dot-viewer`
+/// or `~/.local/share/dot-viewer` by default.
+pub(crate) fn data_dir() -> Option<PathBuf> {
+    xdg_dir("XDG_DATA_HOME", ".local/share")
+}
+
+fn xdg_dir(var: &str, fallback: &str) -> Option<PathBuf> {
+    let base = env::var_os(var)
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(fallback)))?;
+
+    Some(base.join("dot-viewer"))
+}
@@ -0,0 +1,271 @@
+use crate::viewer::error::{DotViewerError, DotViewerResult};
+
+use std::collections::{BTreeSet, HashMap};
+use std::io::Write;
+use std::path::Path;
+
+use dot_graph::{Edge, Graph, Node};
+use serde_json::{json, Map, Value};
+
+/// `Format` is a file format that a graph can be exported to, chosen by the file extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Format {
+    Dot,
+    /// Node-link layout, as read by `networkx.node_link_graph`
+    Json,
+    GraphML,
+    Mermaid,
+    Gml,
+}
+
+impl Format {
+    pub const EXTENSIONS: [&'static str; 5] = ["dot", "json", "graphml", "mmd", "gml"];
+
+    pub fn from_filename(filename: &str) -> Option<Self> {
+        let extension = Path::new(filename).extension()?.to_str()?;
+
+        match extension {
+            "dot" => Some(Self::Dot),
+            "json" => Some(Self::Json),
+            "graphml" => Some(Self::GraphML),
+            "mmd" => Some(Self::Mermaid),
+            "gml" => Some(Self::Gml),
+            _ => None,
+        }
+    }
+
+    /// Format to export to `path` in, by its extension, or an error naming the known extensions.
+    pub fn from_path(path: &Path) -> DotViewerResult<Self> {
+        let filename = path.to_string_lossy();
+
+        Self::from_filename(&filename).ok_or_else(|| {
+            let extensions = Self::EXTENSIONS.map(|ext| format!(".{ext}")).join(", ");
+            DotViewerError::CommandError(format!(
+                "invalid filename: {filename}, expected one of {extensions}"
+            ))
+        })
+    }
+
+    /// Write `graph` in this format to `writer`.
+    pub fn write(&self, graph: &Graph, writer: &mut dyn Write) -> DotViewerResult<()> {
+        match self {
+            Self::Dot => Ok(graph.to_dot(writer)?),
+            Self::Json => write_json(graph, writer),
+            Self::GraphML => write_graphml(graph, writer),
+            Self::Mermaid => write_mermaid(graph, writer),
+            Self::Gml => write_gml(graph, writer),
+        }
+    }
+}
+
+/// Collect nodes and edges of `graph`, sorted by their ids for a stable output.
+fn collect(graph: &Graph) -> DotViewerResult<(Vec<&Node>, Vec<&Edge>)> {
+    let mut nodes = Vec::from_iter(graph.collect_nodes(graph.id())?);
+    nodes.sort_unstable_by(|a, b| a.id().cmp(b.id()));
+
+    let mut edges = Vec::from_iter(graph.collect_edges(graph.id())?);
+    edges.sort_unstable_by(|a, b| (a.from(), a.to()).cmp(&(b.from(), b.to())));
+
+    Ok((nodes, edges))
+}
+
+fn write_json(graph: &Graph, writer: &mut dyn Write) -> DotViewerResult<()> {
+    let (nodes, edges) = collect(graph)?;
+
+    let nodes: Vec<Value> = (nodes.iter())
+        .map(|node| {
+            let mut object = attrs_object(node.attrs(), &["id"]);
+            object.insert("id".to_string(), json!(node.id()));
+            Value::Object(object)
+        })
+        .collect();
+
+    // parallel edges are told apart by a key, counting from 0 for each pair of nodes
+    let mut keys: HashMap<(&String, &String), usize> = HashMap::new();
+    let keys: Vec<usize> = (edges.iter())
+        .map(|edge| {
+            let key = keys.entry((edge.from(), edge.to())).or_default();
+            *key += 1;
+            *key - 1
+        })
+        .collect();
+    let multigraph = keys.iter().any(|&key| key > 0);
+
+    let links: Vec<Value> = (edges.iter().zip(keys))
+        .map(|(edge, key)| {
+            let mut object = attrs_object(edge.attrs(), &["source", "target", "key"]);
+            object.insert("source".to_string(), json!(edge.from()));
+            object.insert("target".to_string(), json!(edge.to()));
+            if multigraph {
+                object.insert("key".to_string(), json!(key));
+            }
+            Value::Object(object)
+        })
+        .collect();
+
+    let value = json!({
+        "directed": true,
+        "multigraph": multigraph,
+        "graph": { "id": graph.id() },
+        "nodes": nodes,
+        "links": links,
+    });
+
+    serde_json::to_writer_pretty(&mut *writer, &value).map_err(std::io::Error::from)?;
+    writeln!(writer)?;
+
+    Ok(())
+}
+
+/// Attributes as a JSON object, skipping those named as the `reserved` structural fields.
+fn attrs_object(attrs: &HashMap<String, String>, reserved: &[&str]) -> Map<String, Value> {
+    (attrs.iter())
+        .filter(|(key, _)| !reserved.contains(&key.as_str()))
+        .map(|(key, value)| (key.clone(), json!(value)))
+        .collect()
+}
+
+fn write_graphml(graph: &Graph, writer: &mut dyn Write) -> DotViewerResult<()> {
+    let (nodes, edges) = collect(graph)?;
+
+    let node_keys: BTreeSet<&String> = nodes.iter().flat_map(|node| node.attrs().keys()).collect();
+    let edge_keys: BTreeSet<&String> = edges.iter().flat_map(|edge| edge.attrs().keys()).collect();
+
+    writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(writer, r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns">"#)?;
+
+    for key in &node_keys {
+        let key = escape_xml(key);
+        writeln!(
+            writer,
+            r#"  <key id="n_{key}" for="node" attr.name="{key}" attr.type="string"/>"#
+        )?;
+    }
+    for key in &edge_keys {
+        let key = escape_xml(key);
+        writeln!(
+            writer,
+            r#"  <key id="e_{key}" for="edge" attr.name="{key}" attr.type="string"/>"#
+        )?;
+    }
+
+    writeln!(writer, r#"  <graph id="{}" edgedefault="directed">"#, escape_xml(graph.id()))?;
+
+    for node in nodes {
+        writeln!(writer, r#"    <node id="{}">"#, escape_xml(node.id()))?;
+        write_graphml_data(writer, "n", node.attrs())?;
+        writeln!(writer, "    </node>")?;
+    }
+
+    for edge in edges {
+        let source = escape_xml(edge.from());
+        let target = escape_xml(edge.to());
+        writeln!(writer, r#"    <edge source="{source}" target="{target}">"#)?;
+        write_graphml_data(writer, "e", edge.attrs())?;
+        writeln!(writer, "    </edge>")?;
+    }
+
+    writeln!(writer, "  </graph>")?;
+    writeln!(writer, "</graphml>")?;
+
+    Ok(())
+}
+
+fn write_graphml_data(
+    writer: &mut dyn Write,
+    prefix: &str,
+    attrs: &HashMap<String, String>,
+) -> DotViewerResult<()> {
+    let mut attrs = Vec::from_iter(attrs);
+    attrs.sort_unstable();
+
+    for (key, value) in attrs {
+        let key = escape_xml(key);
+        let value = escape_xml(value);
+        writeln!(writer, r#"      <data key="{prefix}_{key}">{value}</data>"#)?;
+    }
+
+    Ok(())
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn write_mermaid(graph: &Graph, writer: &mut dyn Write) -> DotViewerResult<()> {
+    let (nodes, edges) = collect(graph)?;
+
+    // mermaid ids are restricted, so nodes are numbered and labeled with their dot ids
+    let idxs: HashMap<&String, usize> =
+        nodes.iter().enumerate().map(|(idx, node)| (node.id(), idx)).collect();
+
+    writeln!(writer, "flowchart TD")?;
+
+    for (idx, node) in nodes.iter().enumerate() {
+        let label = node.id().replace('"', "#quot;");
+        writeln!(writer, "    n{idx}[\"{label}\"]")?;
+    }
+
+    for edge in edges {
+        writeln!(writer, "    n{} --> n{}", idxs[edge.from()], idxs[edge.to()])?;
+    }
+
+    Ok(())
+}
+
+fn write_gml(graph: &Graph, writer: &mut dyn Write) -> DotViewerResult<()> {
+    let (nodes, edges) = collect(graph)?;
+
+    // gml ids are integers, so nodes are numbered and labeled with their dot ids
+    let idxs: HashMap<&String, usize> =
+        nodes.iter().enumerate().map(|(idx, node)| (node.id(), idx)).collect();
+
+    writeln!(writer, "graph [")?;
+    writeln!(writer, "  directed 1")?;
+    writeln!(writer, "  name \"{}\"", escape_gml(graph.id()))?;
+
+    for (idx, node) in nodes.iter().enumerate() {
+        writeln!(writer, "  node [")?;
+        writeln!(writer, "    id {idx}")?;
+        writeln!(writer, "    label \"{}\"", escape_gml(node.id()))?;
+        write_gml_attrs(writer, node.attrs())?;
+        writeln!(writer, "  ]")?;
+    }
+
+    for edge in edges {
+        writeln!(writer, "  edge [")?;
+        writeln!(writer, "    source {}", idxs[edge.from()])?;
+        writeln!(writer, "    target {}", idxs[edge.to()])?;
+        write_gml_attrs(writer, edge.attrs())?;
+        writeln!(writer, "  ]")?;
+    }
+
+    writeln!(writer, "]")?;
+
+    Ok(())
+}
+
+fn write_gml_attrs(writer: &mut dyn Write, attrs: &HashMap<String, String>) -> DotViewerResult<()> {
+    let mut attrs = Vec::from_iter(attrs);
+    attrs.sort_unstable();
+
+    for (key, value) in attrs {
+        // gml keys are alphanumeric, starting with a letter
+        let key: String = key.chars().filter(char::is_ascii_alphanumeric).collect();
+        if !key.starts_with(|c: char| c.is_ascii_alphabetic()) || key == "id" || key == "label" {
+            continue;
+        }
+
+        writeln!(writer, "    {key} \"{}\"", escape_gml(value))?;
+    }
+
+    Ok(())
+}
+
+fn escape_gml(s: &str) -> String {
+    s.replace('&', "&amp;").replace('"', "&quot;")
+}
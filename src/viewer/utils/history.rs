@@ -0,0 +1,122 @@
+use std::fs;
+use std::path::PathBuf;
+
+use log::warn;
+
+const CAPACITY: usize = 1000;
+
+/// `History` holds previous inputs of a mode, persisted in a file across sessions.
+#[derive(Default)]
+pub(crate) struct History {
+    path: Option<PathBuf>,
+    entries: Vec<String>,
+    /// Index of the entry being recalled, which is `entries.len()` when not recalling
+    cursor: usize,
+    /// Input that was being typed before recalling
+    draft: String,
+    /// Keyword of an ongoing reverse search
+    query: Option<String>,
+}
+
+impl History {
+    /// Load history from the file at `path`, if any.
+    pub fn load(path: Option<PathBuf>) -> Self {
+        let entries: Vec<String> = (path.as_ref())
+            .and_then(|path| fs::read_to_string(path).ok())
+            .map(|history| history.lines().map(String::from).collect())
+            .unwrap_or_default();
+        let cursor = entries.len();
+
+        Self { path, entries, cursor, ..Default::default() }
+    }
+
+    /// Append an entry, then save the history to its file.
+    pub fn push(&mut self, entry: String) {
+        if !entry.is_empty() && self.entries.last() != Some(&entry) {
+            self.entries.push(entry);
+
+            if self.entries.len() > CAPACITY {
+                self.entries.drain(..self.entries.len() - CAPACITY);
+            }
+
+            if let Err(err) = self.save() {
+                warn!("failed to save history: {err}");
+            }
+        }
+
+        self.reset();
+    }
+
+    /// Stop recalling, going back to the latest entry.
+    pub fn reset(&mut self) {
+        self.cursor = self.entries.len();
+        self.draft.clear();
+        self.query = None;
+    }
+
+    /// Recall the entry before the one being recalled, given the currently typed `key`.
+    pub fn previous(&mut self, key: &str) -> Option<String> {
+        self.query = None;
+
+        if self.cursor == self.entries.len() {
+            self.draft = key.to_string();
+        }
+
+        if self.cursor == 0 {
+            return None;
+        }
+
+        self.cursor -= 1;
+        Some(self.entries[self.cursor].clone())
+    }
+
+    /// Recall the entry after the one being recalled, or the draft past the latest entry.
+    pub fn next(&mut self) -> Option<String> {
+        self.query = None;
+
+        if self.cursor == self.entries.len() {
+            return None;
+        }
+
+        self.cursor += 1;
+        if self.cursor == self.entries.len() {
+            Some(self.draft.clone())
+        } else {
+            Some(self.entries[self.cursor].clone())
+        }
+    }
+
+    /// Search backwards for an entry containing `key`.
+    /// Repeated searches continue from the last found entry with the same keyword.
+    pub fn search(&mut self, key: &str) -> Option<String> {
+        if self.cursor == self.entries.len() {
+            self.draft = key.to_string();
+        }
+
+        let query = self.query.get_or_insert_with(|| key.to_string());
+
+        let idx = self.entries[..self.cursor].iter().rposition(|entry| entry.contains(&*query))?;
+        self.cursor = idx;
+
+        Some(self.entries[idx].clone())
+    }
+
+    /// Stop an ongoing reverse search, so that the next search starts with a new keyword.
+    pub fn stop_search(&mut self) {
+        self.query = None;
+    }
+
+    fn save(&self) -> std::io::Result<()> {
+        if let Some(path) = &self.path {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+
+            let mut history = self.entries.join("\n");
+            history.push('\n');
+            fs::write(path, history)?;
+        }
+
+        Ok(())
+    }
+}
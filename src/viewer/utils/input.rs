@@ -4,7 +4,6 @@
 pub(crate) struct Input {
     pub key: String,
     pub cursor: usize,
-    history: Vec<String>,
 }
 
 impl Input {
@@ -42,7 +41,6 @@ impl Input {
     }
 
     pub fn clear(&mut self) {
-        self.history.push(self.key.clone());
         self.key = String::from("");
         self.cursor = 0;
     }
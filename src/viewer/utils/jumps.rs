@@ -0,0 +1,79 @@
+use crate::viewer::utils::List;
+
+/// `Jumps` holds the jump list of a view, to go back and forth between visited nodes.
+pub(crate) struct Jumps {
+    /// Visited nodes, from the oldest
+    pub list: List<String>,
+    /// Position in the list, which is the list length if the current node is not recorded yet
+    pub idx: usize,
+}
+
+impl Default for Jumps {
+    fn default() -> Self {
+        Self { list: List::from_iter(Vec::new()), idx: 0 }
+    }
+}
+
+impl Jumps {
+    /// Record a jump from the node `id`, discarding jumps after the current position.
+    pub fn record(&mut self, id: String) {
+        let items = &mut self.list.items;
+
+        items.truncate(self.idx);
+        if items.last() != Some(&id) {
+            items.push(id);
+        }
+
+        self.idx = items.len();
+    }
+
+    /// Go back to the previous position, given the `current` node.
+    pub fn back(&mut self, current: String) -> Option<String> {
+        if self.idx == 0 {
+            return None;
+        }
+
+        self.save(current);
+        if self.idx == 0 {
+            return None;
+        }
+
+        self.idx -= 1;
+        Some(self.list.items[self.idx].clone())
+    }
+
+    /// Go forward to the next position.
+    pub fn forward(&mut self) -> Option<String> {
+        if self.idx + 1 >= self.list.items.len() {
+            return None;
+        }
+
+        self.idx += 1;
+        Some(self.list.items[self.idx].clone())
+    }
+
+    /// Go to the position `idx`, given the `current` node.
+    pub fn jump(&mut self, idx: usize, current: String) -> Option<String> {
+        if idx >= self.list.items.len() {
+            return None;
+        }
+
+        self.save(current);
+
+        self.idx = idx;
+        Some(self.list.items[idx].clone())
+    }
+
+    /// Record the `current` node if it is not yet, so that it can be jumped back to.
+    fn save(&mut self, current: String) {
+        let items = &mut self.list.items;
+
+        if self.idx == items.len() {
+            if items.last() == Some(&current) {
+                self.idx -= 1;
+            } else {
+                items.push(current);
+            }
+        }
+    }
+}
@@ -1,11 +1,15 @@
 #![allow(dead_code)]
 
-use tui::widgets::ListState;
+use tui::{layout::Rect, widgets::ListState};
 
 // https://github.com/fdehau/tui-rs/blob/master/examples/list.rs
 pub(crate) struct List<T> {
     pub state: ListState,
     pub items: Vec<T>,
+    /// Area that the items were last drawn on
+    pub area: Rect,
+    /// Index of the topmost item drawn
+    pub offset: usize,
 }
 
 impl<T: Clone + Eq> std::iter::FromIterator<T> for List<T> {
@@ -14,7 +18,10 @@ impl<T: Clone + Eq> std::iter::FromIterator<T> for List<T> {
 
         let items = Vec::from_iter(iter);
 
-        let mut list = Self { state, items };
+        let area = Rect::default();
+        let offset = 0;
+
+        let mut list = Self { state, items, area, offset };
 
         if !list.items.is_empty() {
             list.state.select(Some(0));
@@ -70,4 +77,42 @@ impl<T: Clone + Eq> List<T> {
     pub fn find(&self, key: T) -> Option<usize> {
         self.items.iter().position(|item| *item == key)
     }
+
+    /// Record the `area` that the items are drawn on,
+    /// keeping track of the scroll offset in the same way as `tui` does.
+    pub fn place(&mut self, area: Rect) {
+        self.area = area;
+
+        let len = self.items.len();
+        let height = area.height as usize;
+        if len == 0 || height == 0 {
+            self.offset = 0;
+            return;
+        }
+
+        let selected = self.state.selected().unwrap_or(0).min(len - 1);
+
+        let mut start = self.offset.min(len - 1);
+        let end = (start + height).min(len);
+        if selected >= end {
+            start = start.max((selected + 1).saturating_sub(height));
+        }
+        if selected < start {
+            start = selected;
+        }
+
+        self.offset = start;
+    }
+
+    /// Index of the item drawn at the position `(x, y)`, if any.
+    pub fn at(&self, x: u16, y: u16) -> Option<usize> {
+        let area = self.area;
+        let inside = area.left() <= x && x < area.right() && area.top() <= y && y < area.bottom();
+        if !inside {
+            return None;
+        }
+
+        let idx = self.offset + (y - area.top()) as usize;
+        (idx < self.items.len()).then_some(idx)
+    }
 }
@@ -0,0 +1,71 @@
+use crate::viewer::utils::List;
+
+use std::fs;
+use std::path::PathBuf;
+
+use log::warn;
+
+/// `Marks` holds named marks on nodes, persisted in a sidecar file next to the dot file.
+pub(crate) struct Marks {
+    path: Option<PathBuf>,
+    /// Marks sorted by name, each with the id of the marked node
+    pub list: List<(char, String)>,
+}
+
+impl Marks {
+    /// Load marks from the file at `path`, if any.
+    pub fn load(path: Option<PathBuf>) -> Self {
+        let mut marks: Vec<(char, String)> = (path.as_ref())
+            .and_then(|path| fs::read_to_string(path).ok())
+            .map(|marks| marks.lines().filter_map(parse).collect())
+            .unwrap_or_default();
+        marks.sort();
+
+        let list = List::from_iter(marks);
+
+        Self { path, list }
+    }
+
+    /// Mark the node `id` with `name`, then save marks to the sidecar file.
+    pub fn set(&mut self, name: char, id: String) {
+        let items = &mut self.list.items;
+
+        match items.binary_search_by_key(&name, |(n, _)| *n) {
+            Ok(idx) => items[idx].1 = id,
+            Err(idx) => items.insert(idx, (name, id)),
+        }
+
+        if self.list.state.selected().is_none() {
+            self.list.first();
+        }
+
+        if let Err(err) = self.save() {
+            warn!("failed to save marks: {err}");
+        }
+    }
+
+    /// Get the id of the node marked with `name`.
+    pub fn get(&self, name: char) -> Option<String> {
+        self.list.items.iter().find(|(n, _)| *n == name).map(|(_, id)| id.clone())
+    }
+
+    fn save(&self) -> std::io::Result<()> {
+        if let Some(path) = &self.path {
+            let marks: String =
+                self.list.items.iter().map(|(name, id)| format!("{name}\t{id}\n")).collect();
+            fs::write(path, marks)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn parse(line: &str) -> Option<(char, String)> {
+    let (name, id) = line.split_once('\t')?;
+
+    let mut chars = name.chars();
+    match (chars.next(), chars.next()) {
+        (Some(name), None) => Some((name, id.to_string())),
+        _ => None,
+    }
+}
@@ -1,13 +1,37 @@
+mod critical;
+mod cycles;
+mod dirs;
+mod format;
+mod history;
 mod input;
+mod jumps;
 mod list;
+mod marks;
+mod query;
+mod render;
+mod source;
+mod stats;
 mod table;
 mod tabs;
+mod traverse;
 mod tree;
 mod trie;
 
+pub(crate) use critical::Critical;
+pub(crate) use cycles::{condensation, Cycle};
+pub(crate) use dirs::{config_dir, data_dir, state_dir};
+pub(crate) use format::Format;
+pub(crate) use history::History;
 pub(crate) use input::Input;
+pub(crate) use jumps::Jumps;
 pub(crate) use list::List;
+pub(crate) use marks::Marks;
+pub(crate) use query::{number, Query};
+pub(crate) use render::{render, render_format, ENGINES};
+pub(crate) use source::{is_stdin, load_graph};
+pub(crate) use stats::Stats;
 pub(crate) use table::Table;
 pub(crate) use tabs::Tabs;
+pub(crate) use traverse::{reachable, shortest_path, Direction};
 pub(crate) use tree::Tree;
 pub(crate) use trie::Trie;
@@ -0,0 +1,368 @@
+use crate::viewer::error::{DotViewerError, DotViewerResult};
+
+use std::cmp::Ordering;
+use std::collections::HashSet;
+
+use dot_graph::Graph;
+use regex::Regex;
+
+/// `Query` is a predicate on nodes, parsed from a query search input, e.g.,
+///
+/// ```text
+/// shape == box and label ~ Conv and (indegree > 3 or not subgraph == cluster_0)
+/// ```
+///
+/// A predicate compares a field with a value by `==`, `!=`, `<`, `<=`, `>`, `>=`,
+/// or matches it with a regex by `~` and `!~`.
+/// Fields are `id`, `indegree`, `outdegree`, `degree`, `subgraph`, or an attribute key.
+/// A bare field holds if the node has the attribute.
+/// Values are compared as numbers if both sides are numbers, and as strings otherwise.
+#[derive(Debug)]
+pub(crate) enum Query {
+    Or(Box<Query>, Box<Query>),
+    And(Box<Query>, Box<Query>),
+    Not(Box<Query>),
+    Has(String),
+    Compare(Field, Op, Value),
+    /// Membership in subgraphs, resolved to the set of their nodes
+    Subgraph(HashSet<String>),
+}
+
+#[derive(Debug)]
+pub(crate) enum Field {
+    Id,
+    InDegree,
+    OutDegree,
+    Degree,
+    Attr(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Match,
+    NotMatch,
+}
+
+#[derive(Debug)]
+pub(crate) struct Value {
+    raw: String,
+    number: Option<f64>,
+    regex: Option<Regex>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    LParen,
+    RParen,
+    And,
+    Or,
+    Not,
+    Op(Op),
+    Word(String),
+}
+
+impl Query {
+    /// Parse a query from `input`, resolving subgraph predicates against `graph`.
+    pub fn new(input: &str, graph: &Graph) -> DotViewerResult<Self> {
+        let tokens = tokenize(input)?;
+        if tokens.is_empty() {
+            return Err(DotViewerError::QueryError("empty query".to_string()));
+        }
+
+        let mut parser = Parser { tokens, pos: 0, graph };
+        let query = parser.or()?;
+
+        match parser.peek() {
+            None => Ok(query),
+            Some(token) => Err(DotViewerError::QueryError(format!("unexpected {token:?}"))),
+        }
+    }
+
+    /// Whether the node `id` in `graph` satisfies the query.
+    pub fn eval(&self, id: &str, graph: &Graph) -> bool {
+        match self {
+            Self::Or(lhs, rhs) => lhs.eval(id, graph) || rhs.eval(id, graph),
+            Self::And(lhs, rhs) => lhs.eval(id, graph) && rhs.eval(id, graph),
+            Self::Not(query) => !query.eval(id, graph),
+            Self::Has(key) => attr(id, key, graph).is_some(),
+            Self::Compare(field, op, value) => {
+                let lhs = match field {
+                    Field::Id => Some(id.to_string()),
+                    Field::InDegree => degree(id, graph, true, false),
+                    Field::OutDegree => degree(id, graph, false, true),
+                    Field::Degree => degree(id, graph, true, true),
+                    Field::Attr(key) => attr(id, key, graph),
+                };
+
+                // a missing attribute only differs from any value
+                lhs.map_or(*op == Op::Ne || *op == Op::NotMatch, |lhs| compare(&lhs, *op, value))
+            }
+            Self::Subgraph(ids) => ids.contains(id),
+        }
+    }
+}
+
+fn attr(id: &str, key: &str, graph: &Graph) -> Option<String> {
+    let node = graph.search_node(&id.to_string())?;
+    node.attrs().get(key).cloned()
+}
+
+fn degree(id: &str, graph: &Graph, froms: bool, tos: bool) -> Option<String> {
+    let id = id.to_string();
+
+    let mut degree = 0;
+    if froms {
+        degree += graph.froms(&id).ok()?.len();
+    }
+    if tos {
+        degree += graph.tos(&id).ok()?.len();
+    }
+
+    Some(degree.to_string())
+}
+
+fn compare(lhs: &str, op: Op, value: &Value) -> bool {
+    if let Some(regex) = &value.regex {
+        return regex.is_match(lhs) == (op == Op::Match);
+    }
+
+    let ordering = match (number(lhs), value.number) {
+        (Some(lhs), Some(rhs)) => lhs.partial_cmp(&rhs),
+        _ => Some(lhs.cmp(&value.raw)),
+    };
+    let Some(ordering) = ordering else {
+        return op == Op::Ne;
+    };
+
+    match op {
+        Op::Eq => ordering == Ordering::Equal,
+        Op::Ne => ordering != Ordering::Equal,
+        Op::Lt => ordering == Ordering::Less,
+        Op::Le => ordering != Ordering::Greater,
+        Op::Gt => ordering == Ordering::Greater,
+        Op::Ge => ordering != Ordering::Less,
+        Op::Match | Op::NotMatch => unreachable!(),
+    }
+}
+
+/// Numeric value of an attribute or query value, ignoring the quotes dot may keep around it.
+pub(crate) fn number(value: &str) -> Option<f64> {
+    value.trim_matches('"').trim().parse().ok()
+}
+
+fn tokenize(input: &str) -> DotViewerResult<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        let token = match c {
+            _ if c.is_whitespace() => {
+                chars.next();
+                continue;
+            }
+            '(' => {
+                chars.next();
+                Token::LParen
+            }
+            ')' => {
+                chars.next();
+                Token::RParen
+            }
+            '"' => {
+                chars.next();
+
+                let mut word = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => word.extend(chars.next()),
+                        Some(c) => word.push(c),
+                        None => {
+                            let err = "unterminated string".to_string();
+                            return Err(DotViewerError::QueryError(err));
+                        }
+                    }
+                }
+
+                Token::Word(word)
+            }
+            '=' | '!' | '<' | '>' | '~' | '&' | '|' => {
+                let mut op = String::new();
+                while let Some(&c) = chars.peek() {
+                    if !"=!<>~&|".contains(c) {
+                        break;
+                    }
+                    op.push(c);
+                    chars.next();
+                }
+
+                match op.as_str() {
+                    "=" | "==" => Token::Op(Op::Eq),
+                    "!=" => Token::Op(Op::Ne),
+                    "<" => Token::Op(Op::Lt),
+                    "<=" => Token::Op(Op::Le),
+                    ">" => Token::Op(Op::Gt),
+                    ">=" => Token::Op(Op::Ge),
+                    "~" => Token::Op(Op::Match),
+                    "!~" => Token::Op(Op::NotMatch),
+                    "&&" => Token::And,
+                    "||" => Token::Or,
+                    "!" => Token::Not,
+                    _ => return Err(DotViewerError::QueryError(format!("unknown operator {op}"))),
+                }
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || "()\"=!<>~&|".contains(c) {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+
+                match word.as_str() {
+                    "and" => Token::And,
+                    "or" => Token::Or,
+                    "not" => Token::Not,
+                    _ => Token::Word(word),
+                }
+            }
+        };
+
+        tokens.push(token);
+    }
+
+    Ok(tokens)
+}
+
+/// Recursive descent parser, where `not` binds tighter than `and`, and `and` than `or`.
+struct Parser<'a> {
+    tokens: Vec<Token>,
+    pos: usize,
+    graph: &'a Graph,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn or(&mut self) -> DotViewerResult<Query> {
+        let mut query = self.and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.next();
+            query = Query::Or(Box::new(query), Box::new(self.and()?));
+        }
+
+        Ok(query)
+    }
+
+    fn and(&mut self) -> DotViewerResult<Query> {
+        let mut query = self.not()?;
+        while self.peek() == Some(&Token::And) {
+            self.next();
+            query = Query::And(Box::new(query), Box::new(self.not()?));
+        }
+
+        Ok(query)
+    }
+
+    fn not(&mut self) -> DotViewerResult<Query> {
+        if self.peek() == Some(&Token::Not) {
+            self.next();
+            return Ok(Query::Not(Box::new(self.not()?)));
+        }
+
+        self.atom()
+    }
+
+    fn atom(&mut self) -> DotViewerResult<Query> {
+        match self.next() {
+            Some(Token::LParen) => {
+                let query = self.or()?;
+                match self.next() {
+                    Some(Token::RParen) => Ok(query),
+                    _ => Err(DotViewerError::QueryError("unclosed parenthesis".to_string())),
+                }
+            }
+            Some(Token::Word(field)) => self.predicate(field),
+            Some(token) => Err(DotViewerError::QueryError(format!("unexpected {token:?}"))),
+            None => Err(DotViewerError::QueryError("unexpected end of query".to_string())),
+        }
+    }
+
+    fn predicate(&mut self, field: String) -> DotViewerResult<Query> {
+        let op = match self.peek() {
+            Some(Token::Op(op)) => *op,
+            _ => return Ok(Query::Has(field)),
+        };
+        self.next();
+
+        let raw = match self.next() {
+            Some(Token::Word(raw)) => raw,
+            _ => return Err(DotViewerError::QueryError(format!("no value to compare {field}"))),
+        };
+
+        let regex = match op {
+            Op::Match | Op::NotMatch => {
+                Some(Regex::new(&raw).map_err(|e| DotViewerError::QueryError(e.to_string()))?)
+            }
+            _ => None,
+        };
+
+        let field = match field.as_str() {
+            "subgraph" => return self.subgraph(op, &raw, regex),
+            "id" => Field::Id,
+            "indegree" => Field::InDegree,
+            "outdegree" => Field::OutDegree,
+            "degree" => Field::Degree,
+            _ => Field::Attr(field),
+        };
+
+        let number = number(&raw);
+        Ok(Query::Compare(field, op, Value { raw, number, regex }))
+    }
+
+    /// Resolve membership in the subgraph `raw`, or in subgraphs matching the regex.
+    fn subgraph(&self, op: Op, raw: &str, regex: Option<Regex>) -> DotViewerResult<Query> {
+        let graph = self.graph;
+
+        let subgraphs: Vec<String> = match (op, regex) {
+            (Op::Eq | Op::Ne, _) => vec![raw.to_string()],
+            (Op::Match | Op::NotMatch, Some(regex)) => {
+                let subgraphs = graph.collect_subgraphs(graph.id())?;
+                subgraphs.into_iter().filter(|id| regex.is_match(id)).cloned().collect()
+            }
+            _ => {
+                let err = "subgraph is compared by ==, !=, ~ or !~".to_string();
+                return Err(DotViewerError::QueryError(err));
+            }
+        };
+
+        let mut ids = HashSet::new();
+        for subgraph in &subgraphs {
+            let nodes = (graph.collect_nodes(subgraph))
+                .map_err(|_| DotViewerError::QueryError(format!("no such subgraph {subgraph}")))?;
+            ids.extend(nodes.into_iter().map(|node| node.id().clone()));
+        }
+
+        let query = Query::Subgraph(ids);
+        match op {
+            Op::Ne | Op::NotMatch => Ok(Query::Not(Box::new(query))),
+            _ => Ok(query),
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use crate::viewer::error::{DotViewerError, DotViewerResult};
+
+use std::ffi::{c_char, c_int, CString};
+use std::path::Path;
+
+use dot_graph::Graph;
+
+/// Layout engines of Graphviz that a graph can be rendered with.
+pub(crate) const ENGINES: [&str; 3] = ["dot", "neato", "sfdp"];
+
+/// Output formats of Graphviz that a graph can be rendered to, chosen by the file extension.
+pub(crate) const FORMATS: [&str; 3] = ["svg", "png", "pdf"];
+
+/// Opaque `GVC_t` of Graphviz
+#[repr(C)]
+struct RawContext {
+    _private: [u8; 0],
+}
+
+/// Opaque `Agraph_t` of Graphviz
+#[repr(C)]
+struct RawGraph {
+    _private: [u8; 0],
+}
+
+#[link(name = "cgraph")]
+extern "C" {
+    fn agmemread(cp: *const c_char) -> *mut RawGraph;
+    fn agclose(g: *mut RawGraph) -> c_int;
+}
+
+#[link(name = "gvc")]
+extern "C" {
+    fn gvContext() -> *mut RawContext;
+    fn gvFreeContext(gvc: *mut RawContext) -> c_int;
+    fn gvLayout(gvc: *mut RawContext, g: *mut RawGraph, engine: *const c_char) -> c_int;
+    fn gvFreeLayout(gvc: *mut RawContext, g: *mut RawGraph) -> c_int;
+    fn gvRenderFilename(
+        gvc: *mut RawContext,
+        g: *mut RawGraph,
+        format: *const c_char,
+        filename: *const c_char,
+    ) -> c_int;
+}
+
+/// Graphviz context, freed on drop.
+struct Context(*mut RawContext);
+
+impl Drop for Context {
+    fn drop(&mut self) {
+        unsafe { gvFreeContext(self.0) };
+    }
+}
+
+/// Graph read by Graphviz, closed on drop.
+struct Agraph(*mut RawGraph);
+
+impl Drop for Agraph {
+    fn drop(&mut self) {
+        unsafe { agclose(self.0) };
+    }
+}
+
+/// Lay out `graph` with the Graphviz `engine`, and render it to `path`
+/// in the format of its extension.
+pub(crate) fn render(graph: &Graph, engine: &str, path: &Path) -> DotViewerResult<()> {
+    let format = render_format(path)?;
+
+    if !ENGINES.contains(&engine) {
+        return Err(DotViewerError::RenderError(format!("find layout engine {engine}")));
+    }
+
+    let mut dot = Vec::new();
+    graph.to_dot(&mut dot)?;
+
+    let dot = cstring(dot)?;
+    let engine = cstring(engine)?;
+    let format = cstring(format)?;
+    let filename = cstring(path.to_string_lossy().as_bytes())?;
+
+    // SAFETY: all pointers passed to Graphviz are either null-checked handles owned by the
+    // guards above, or valid nul-terminated strings that outlive the calls
+    unsafe {
+        let context = gvContext();
+        if context.is_null() {
+            return Err(DotViewerError::RenderError("create a context".to_string()));
+        }
+        let context = Context(context);
+
+        let agraph = agmemread(dot.as_ptr());
+        if agraph.is_null() {
+            return Err(DotViewerError::RenderError("read the graph".to_string()));
+        }
+        let agraph = Agraph(agraph);
+
+        if gvLayout(context.0, agraph.0, engine.as_ptr()) != 0 {
+            let engine = engine.to_string_lossy();
+            return Err(DotViewerError::RenderError(format!("lay out the graph with {engine}")));
+        }
+
+        let rendered = gvRenderFilename(context.0, agraph.0, format.as_ptr(), filename.as_ptr());
+        gvFreeLayout(context.0, agraph.0);
+
+        if rendered != 0 {
+            let path = path.display();
+            return Err(DotViewerError::RenderError(format!("render to {path}")));
+        }
+    }
+
+    Ok(())
+}
+
+/// Output format to render to `path` in, by its extension.
+pub(crate) fn render_format(path: &Path) -> DotViewerResult<&str> {
+    (path.extension())
+        .and_then(|extension| extension.to_str())
+        .filter(|extension| FORMATS.contains(extension))
+        .ok_or_else(|| {
+            let formats = FORMATS.map(|format| format!(".{format}")).join(", ");
+            DotViewerError::RenderError(format!(
+                "render {}, expected one of {formats}",
+                path.display()
+            ))
+        })
+}
+
+fn cstring(bytes: impl Into<Vec<u8>>) -> DotViewerResult<CString> {
+    CString::new(bytes).map_err(|_| DotViewerError::RenderError("pass a nul byte".to_string()))
+}
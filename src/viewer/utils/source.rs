@@ -0,0 +1,60 @@
+use crate::viewer::error::DotViewerResult;
+
+use std::env;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::process;
+
+use dot_graph::{parser, Graph};
+use flate2::read::MultiGzDecoder;
+
+/// Parse the graph at `path`, which is a dot file, a `.gz` or `.zst` compressed dot file,
+/// or `-` for stdin.
+///
+/// The `dot-graph` parser only takes a path to a plain file, rather than a reader,
+/// so stdin and compressed files are decompressed in full into a temporary file first,
+/// which is removed once parsed.
+/// Gzip files may hold several members, e.g., from appending with `gzip -c >>`, which are read in turn.
+/// The temporary file goes to `env::temp_dir()`, which users can move with `TMPDIR`.
+pub(crate) fn load_graph(path: &str) -> DotViewerResult<Graph> {
+    let reader: Box<dyn Read> = if is_stdin(path) {
+        Box::new(io::stdin().lock())
+    } else if path.ends_with(".gz") {
+        Box::new(MultiGzDecoder::new(fs::File::open(path)?))
+    } else if path.ends_with(".zst") {
+        Box::new(zstd::stream::read::Decoder::new(fs::File::open(path)?)?)
+    } else {
+        return Ok(parser::parse(path)?);
+    };
+
+    let temp = temp_path(path);
+    let result =
+        decompress(reader, &temp).and_then(|_| Ok(parser::parse(&temp.to_string_lossy())?));
+    let _ = fs::remove_file(&temp);
+
+    result
+}
+
+/// Whether `path` is read from a stream rather than a plain dot file,
+/// so it cannot be watched for changes or have sidecar files.
+pub(crate) fn is_stdin(path: &str) -> bool {
+    path == "-"
+}
+
+fn decompress(mut reader: Box<dyn Read>, temp: &Path) -> DotViewerResult<()> {
+    let mut file = fs::File::create(temp)?;
+    io::copy(&mut reader, &mut file)?;
+
+    Ok(())
+}
+
+fn temp_path(path: &str) -> PathBuf {
+    let name = match Path::new(path).file_name() {
+        Some(name) if !is_stdin(path) => name.to_string_lossy(),
+        _ => "stdin.dot".into(),
+    };
+    let name = name.trim_end_matches(".gz").trim_end_matches(".zst");
+
+    env::temp_dir().join(format!("dot-viewer-{}-{name}", process::id()))
+}
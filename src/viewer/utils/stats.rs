@@ -0,0 +1,186 @@
+use crate::viewer::{error::DotViewerResult, utils::List};
+
+use std::collections::{BTreeMap, HashMap};
+
+use dot_graph::Graph;
+
+/// Number of nodes listed for the top fan-in, fan-out and components
+const TOP: usize = 5;
+
+/// Width of the longest bar in the degree histogram
+const BAR: usize = 40;
+
+/// `Stats` holds statistics of a graph as rows of text,
+/// each with the id of a node to go to, if any.
+pub(crate) struct Stats {
+    pub list: List<(String, Option<String>)>,
+}
+
+impl Default for Stats {
+    fn default() -> Self {
+        Self { list: List::from_iter(Vec::new()) }
+    }
+}
+
+impl Stats {
+    /// Compute statistics of `graph`, given its `nodes` in topological order,
+    /// or ordered by strongly connected components if it is `cyclic`.
+    pub fn new(graph: &Graph, nodes: &[String], cyclic: bool) -> DotViewerResult<Self> {
+        let mut rows = Vec::new();
+
+        let edges = graph.collect_edges(graph.id())?.len();
+
+        let mut indegrees = Vec::with_capacity(nodes.len());
+        let mut outdegrees = Vec::with_capacity(nodes.len());
+        for id in nodes {
+            indegrees.push((id, graph.froms(id)?.len()));
+            outdegrees.push((id, graph.tos(id)?.len()));
+        }
+
+        let sources: Vec<&String> =
+            indegrees.iter().filter(|(_, degree)| *degree == 0).map(|(id, _)| *id).collect();
+        let sinks: Vec<&String> =
+            outdegrees.iter().filter(|(_, degree)| *degree == 0).map(|(id, _)| *id).collect();
+
+        rows.push((format!("nodes: {}", nodes.len()), None));
+        rows.push((format!("edges: {edges}"), None));
+        rows.push((
+            format!("sources: {}", sources.len()),
+            sources.first().map(|id| id.to_string()),
+        ));
+        rows.push((format!("sinks: {}", sinks.len()), sinks.first().map(|id| id.to_string())));
+
+        // paths are unbounded through cycles
+        if cyclic {
+            rows.push(("max depth: n/a (cyclic)".to_string(), None));
+        } else {
+            let (depth, from, to) = longest_path(graph, nodes)?;
+            rows.push((format!("max depth: {depth} ({from} -> {to})"), Some(to)));
+        }
+
+        rows.push((String::new(), None));
+        rows.push(("degree histogram:".to_string(), None));
+        let degrees = (indegrees.iter().zip(&outdegrees))
+            .map(|((id, indegree), (_, outdegree))| (*id, indegree + outdegree));
+        rows.extend(histogram(degrees));
+
+        rows.push((String::new(), None));
+        rows.push(("top fan-in:".to_string(), None));
+        rows.extend(top(&indegrees));
+
+        rows.push((String::new(), None));
+        rows.push(("top fan-out:".to_string(), None));
+        rows.extend(top(&outdegrees));
+
+        let components = components(graph, nodes)?;
+        rows.push((String::new(), None));
+        rows.push((format!("weakly connected components: {}", components.len()), None));
+        for (size, id) in components.into_iter().take(TOP) {
+            rows.push((format!("  {size:>6} nodes, e.g. {id}"), Some(id)));
+        }
+
+        let mut list = List::from_iter(rows);
+        list.first();
+
+        Ok(Self { list })
+    }
+
+    /// Id of the node in the selected row, if any.
+    pub fn selected(&self) -> Option<String> {
+        self.list.selected().and_then(|(_, id)| id)
+    }
+}
+
+/// Find a longest path, returning its length in edges and its endpoints.
+fn longest_path(graph: &Graph, nodes: &[String]) -> DotViewerResult<(usize, String, String)> {
+    // for each node, the length of the longest path ending at it, and where the path starts
+    let mut longest: HashMap<&String, (usize, &String)> = HashMap::new();
+
+    for id in nodes {
+        let mut path = (0, id);
+        for from in graph.froms(id)? {
+            if let Some(&(depth, start)) = longest.get(from) {
+                if depth + 1 > path.0 {
+                    path = (depth + 1, start);
+                }
+            }
+        }
+        longest.insert(id, path);
+    }
+
+    let (to, (depth, from)) = (longest.into_iter())
+        .max_by_key(|(_, (depth, _))| *depth)
+        .expect("there is always a node in a view");
+
+    Ok((depth, from.clone(), to.clone()))
+}
+
+/// Bucket total degrees by powers of two, each row going to the first node in the bucket.
+fn histogram<'a>(
+    degrees: impl Iterator<Item = (&'a String, usize)>,
+) -> Vec<(String, Option<String>)> {
+    let mut buckets: BTreeMap<usize, (usize, &String)> = BTreeMap::new();
+    for (id, degree) in degrees {
+        let upper = if degree == 0 { 0 } else { degree.next_power_of_two() };
+        buckets.entry(upper).or_insert((0, id)).0 += 1;
+    }
+
+    let max = buckets.values().map(|(count, _)| *count).max().unwrap_or(1);
+
+    (buckets.into_iter())
+        .map(|(upper, (count, id))| {
+            let lower = if upper <= 2 { upper } else { upper / 2 + 1 };
+            let range = if lower == upper { upper.to_string() } else { format!("{lower}-{upper}") };
+            let bar = "#".repeat((count * BAR).div_ceil(max));
+
+            (format!("  {range:>11} {count:>8}  {bar}"), Some(id.clone()))
+        })
+        .collect()
+}
+
+/// List nodes with the largest degrees.
+fn top(degrees: &[(&String, usize)]) -> Vec<(String, Option<String>)> {
+    let mut degrees = degrees.to_vec();
+    degrees.sort_by(|(a, x), (b, y)| y.cmp(x).then(a.cmp(b)));
+
+    (degrees.into_iter())
+        .take(TOP)
+        .map(|(id, degree)| (format!("  {degree:>6}  {id}"), Some(id.clone())))
+        .collect()
+}
+
+/// Find weakly connected components, returning their sizes and representative nodes,
+/// from the largest.
+fn components(graph: &Graph, nodes: &[String]) -> DotViewerResult<Vec<(usize, String)>> {
+    let idxs: HashMap<&String, usize> = nodes.iter().enumerate().map(|(i, id)| (id, i)).collect();
+    let mut parents: Vec<usize> = (0..nodes.len()).collect();
+
+    fn find(parents: &mut [usize], mut i: usize) -> usize {
+        while parents[i] != i {
+            parents[i] = parents[parents[i]];
+            i = parents[i];
+        }
+        i
+    }
+
+    for (i, id) in nodes.iter().enumerate() {
+        for to in graph.tos(id)? {
+            if let Some(&j) = idxs.get(to) {
+                let (a, b) = (find(&mut parents, i), find(&mut parents, j));
+                parents[a.max(b)] = a.min(b);
+            }
+        }
+    }
+
+    // roots are the smallest index in each component, so the first node in topological order
+    let mut sizes: BTreeMap<usize, usize> = BTreeMap::new();
+    for i in 0..nodes.len() {
+        *sizes.entry(find(&mut parents, i)).or_insert(0) += 1;
+    }
+
+    let mut components: Vec<(usize, String)> =
+        sizes.into_iter().map(|(root, size)| (size, nodes[root].clone())).collect();
+    components.sort_by(|(a, _), (b, _)| b.cmp(a));
+
+    Ok(components)
+}
@@ -2,18 +2,23 @@
 
 use crate::viewer::error::{DotViewerError, DotViewerResult};
 
+use tui::layout::Rect;
+
 // https://github.com/fdehau/tui-rs/blob/master/examples/tabs.rs
 pub(crate) struct Tabs<T> {
     pub state: usize,
     pub tabs: Vec<T>,
+    /// Area that the tab titles were last drawn on
+    pub area: Rect,
 }
 
 impl<T> std::iter::FromIterator<T> for Tabs<T> {
     fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
         let state = 0;
         let tabs = Vec::from_iter(iter);
+        let area = Rect::default();
 
-        Self { state, tabs }
+        Self { state, tabs, area }
     }
 }
 
@@ -0,0 +1,87 @@
+use crate::viewer::error::DotViewerResult;
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use dot_graph::Graph;
+use serde::{Deserialize, Serialize};
+
+/// Direction to follow edges in, while traversing a graph.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub(crate) enum Direction {
+    Forward,
+    Backward,
+}
+
+/// Collect nodes reachable from `seeds` within `depth` hops (or any number of hops if `None`),
+/// following edges in `direction`. Seeds are included in the result.
+pub(crate) fn reachable(
+    graph: &Graph,
+    seeds: &[String],
+    depth: Option<usize>,
+    direction: Direction,
+) -> DotViewerResult<HashSet<String>> {
+    let mut visited: HashSet<String> = seeds.iter().cloned().collect();
+    let mut frontier: Vec<String> = seeds.to_vec();
+
+    let mut hops = 0;
+    while !frontier.is_empty() && depth.is_none_or(|depth| hops < depth) {
+        let mut next = Vec::new();
+        for id in &frontier {
+            for adj in adjacent(graph, id, direction)? {
+                if visited.insert(adj.clone()) {
+                    next.push(adj);
+                }
+            }
+        }
+
+        frontier = next;
+        hops += 1;
+    }
+
+    Ok(visited)
+}
+
+/// Find a shortest path from `from` to `to`, following edges forward.
+pub(crate) fn shortest_path(
+    graph: &Graph,
+    from: &String,
+    to: &String,
+) -> DotViewerResult<Option<Vec<String>>> {
+    let mut parents: HashMap<String, String> = HashMap::new();
+    let mut queue = VecDeque::from([from.clone()]);
+
+    while let Some(id) = queue.pop_front() {
+        if id == *to {
+            let mut path = vec![id];
+            while let Some(parent) = parents.get(path.last().unwrap()) {
+                path.push(parent.clone());
+            }
+            path.reverse();
+
+            return Ok(Some(path));
+        }
+
+        for adj in adjacent(graph, &id, Direction::Forward)? {
+            if adj != *from && !parents.contains_key(&adj) {
+                parents.insert(adj.clone(), id.clone());
+                queue.push_back(adj);
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+/// Collect adjacent nodes of `id`, following edges in `direction`.
+pub(crate) fn adjacent(
+    graph: &Graph,
+    id: &String,
+    direction: Direction,
+) -> DotViewerResult<Vec<String>> {
+    let ids = match direction {
+        Direction::Forward => graph.tos(id)?,
+        Direction::Backward => graph.froms(id)?,
+    };
+
+    Ok(ids.iter().map(|id| id.to_string()).collect())
+}
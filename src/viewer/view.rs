@@ -1,16 +1,25 @@
 use crate::viewer::{
+    diff::Attrs,
     error::{DotViewerError, DotViewerResult},
-    utils::{List, Tree, Trie},
+    modes::SearchMode,
+    recipe::{SetOp, Step},
+    utils::{
+        condensation, reachable, shortest_path, Critical, Cycle, Direction, Jumps, List, Query,
+        Tree, Trie,
+    },
 };
 
+use std::collections::{HashMap, HashSet};
+use std::mem;
+
 use dot_graph::Graph;
 
+use serde::{Deserialize, Serialize};
+
 use fuzzy_matcher::{skim::SkimMatcherV2, FuzzyMatcher};
 use rayon::prelude::*;
 use regex::Regex;
 
-type Matcher = fn(&str, &str, &Graph) -> Option<Vec<usize>>;
-
 /// `View` holds a "view" of the graph that `dot-viewer` is dealing with.
 ///
 /// Named as an analogy to the database concept of "view",
@@ -19,18 +28,31 @@ pub(crate) struct View {
     /// Title of the view
     pub title: String,
 
+    /// Id of the tab holding the view, unique among open tabs
+    pub id: usize,
+    /// Id of the tab that the view was derived from, if any
+    pub parent: Option<usize>,
+
     /// Graph that the view is representing (a portion of the original graph)
     pub graph: Graph,
 
     /// Current focus
     pub focus: Focus,
-    /// Topologically sorted list of all nodes in the view
+    /// Topologically sorted list of all nodes in the view,
+    /// or sorted by strongly connected components if the graph is cyclic
     pub current: List<String>,
     /// List of previous nodes of the currently selected node
     pub prevs: List<String>,
     /// List of next nodes of the currently selected node
     pub nexts: List<String>,
-
+    /// Attributes of the edges in the view, by their endpoint ids,
+    /// with parallel edges kept apart in the order they appear
+    pub edges: HashMap<(String, String), Vec<Attrs>>,
+    /// How the attributes of the selected node are shown
+    pub attrs: AttrsMode,
+
+    /// Search mode of the keyword for match
+    pub smode: SearchMode,
     /// Keyword for match
     pub key: String,
     /// List of matching nodes given some input, with highlight index
@@ -41,21 +63,73 @@ pub(crate) struct View {
 
     /// Tree holding the subgraph tree of the view
     pub subtree: Tree,
+
+    /// Jump list of the view
+    pub jumps: Jumps,
+
+    /// List of strongly connected components with cycles in the view
+    pub cycles: List<Cycle>,
+    /// Nodes that sit on cycles
+    pub cyclic: HashSet<String>,
+
+    /// Critical path of the view, if analyzed
+    pub critical: Option<Critical>,
+
+    /// Steps taken to derive the view from the root view
+    pub recipe: Vec<Step>,
 }
 
-#[derive(PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub(crate) enum Focus {
     Current,
     Prev,
     Next,
 }
 
+/// `AttrsMode` is how the attrs panel shows the selected node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum AttrsMode {
+    /// Label rendered, drawing HTML-like table labels as tables
+    Rendered,
+    /// All attributes, one per line
+    List,
+    /// Raw dot text of the node
+    Raw,
+}
+
+impl AttrsMode {
+    /// Mode to toggle to, cycling through rendered, list and raw.
+    pub fn next(self) -> Self {
+        match self {
+            Self::Rendered => Self::List,
+            Self::List => Self::Raw,
+            Self::Raw => Self::Rendered,
+        }
+    }
+
+    /// Name shown in the title of the attrs panel.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Rendered => "rendered",
+            Self::List => "list",
+            Self::Raw => "raw",
+        }
+    }
+}
+
 impl View {
     /// Constructs a new `View`, given a `title` and a `graph`, which is a portion of the original
     /// graph.
     pub fn new(title: String, graph: Graph) -> DotViewerResult<Self> {
-        let node_ids = graph.topsort()?;
-        let node_ids = node_ids.iter().map(|&id| id.clone());
+        // fall back to ordering by strongly connected components if the graph is cyclic
+        let (node_ids, cycles) = match graph.topsort() {
+            Ok(node_ids) => (node_ids.into_iter().cloned().collect(), Vec::new()),
+            Err(_) => condensation(&graph)?,
+        };
+        let cyclic: HashSet<String> =
+            cycles.iter().flat_map(|cycle| cycle.nodes.iter().cloned()).collect();
+        let cycles = List::from_iter(cycles);
+        let node_ids = node_ids.into_iter();
 
         let trie = Trie::from_iter(node_ids.clone());
 
@@ -63,14 +137,46 @@ impl View {
         let current = List::from_iter(node_ids);
         let prevs = List::from_iter(Vec::new());
         let nexts = List::from_iter(Vec::new());
+        // indexed once, so that moving between nodes does not scan all edges
+        let mut edges: HashMap<(String, String), Vec<Attrs>> = HashMap::new();
+        for edge in graph.collect_edges(graph.id())? {
+            let key = (edge.from().clone(), edge.to().clone());
+            edges.entry(key).or_default().push(edge.attrs().clone());
+        }
+        let attrs = AttrsMode::Rendered;
 
+        let smode = SearchMode::Fuzzy;
         let key = String::new();
         let matches = List::from_iter(Vec::new());
 
         let subtree = Tree::from_graph(&graph);
 
-        let mut view =
-            Self { title, graph, focus, current, prevs, nexts, key, matches, trie, subtree };
+        let jumps = Jumps::default();
+
+        let recipe = Vec::new();
+
+        let mut view = Self {
+            title,
+            id: 0,
+            parent: None,
+            graph,
+            focus,
+            current,
+            prevs,
+            nexts,
+            edges,
+            attrs,
+            smode,
+            key,
+            matches,
+            trie,
+            subtree,
+            jumps,
+            cycles,
+            cyclic,
+            critical: None,
+            recipe,
+        };
 
         view.update_adjacent().expect("there is always a selected current node on initialization");
 
@@ -81,6 +187,8 @@ impl View {
     pub fn goto_first(&mut self) -> DotViewerResult<()> {
         match &self.focus {
             Focus::Current => {
+                let from = self.current_id();
+                self.jumps.record(from);
                 self.current.first();
                 self.update_adjacent()?
             }
@@ -95,6 +203,8 @@ impl View {
     pub fn goto_last(&mut self) -> DotViewerResult<()> {
         match &self.focus {
             Focus::Current => {
+                let from = self.current_id();
+                self.jumps.record(from);
                 self.current.last();
                 self.update_adjacent()?
             }
@@ -124,9 +234,52 @@ impl View {
             })
     }
 
-    /// Navigate to the currently selected node with `id`.
+    /// Navigate to the currently selected node with `id`, recording the jump.
     /// The current node list will be focused on the selected node.
     pub fn goto(&mut self, id: &str) -> DotViewerResult<()> {
+        let from = self.current_id();
+        self.select(id)?;
+
+        if from != id {
+            self.jumps.record(from);
+        }
+
+        Ok(())
+    }
+
+    /// Navigate back in the jump list.
+    pub fn jump_back(&mut self) -> DotViewerResult<()> {
+        let current = self.current_id();
+        let id = (self.jumps)
+            .back(current)
+            .ok_or(DotViewerError::ViewerError("already at the oldest jump".to_string()))?;
+
+        self.select(&id)
+    }
+
+    /// Navigate forward in the jump list.
+    pub fn jump_forward(&mut self) -> DotViewerResult<()> {
+        let id = (self.jumps)
+            .forward()
+            .ok_or(DotViewerError::ViewerError("already at the newest jump".to_string()))?;
+
+        self.select(&id)
+    }
+
+    /// Navigate to the jump selected in the jump list.
+    pub fn jump_selected(&mut self) -> DotViewerResult<()> {
+        let idx = (self.jumps.list.state.selected())
+            .ok_or(DotViewerError::ViewerError("no jump selected".to_string()))?;
+        let current = self.current_id();
+        let id = (self.jumps)
+            .jump(idx, current)
+            .ok_or(DotViewerError::ViewerError("no jump selected".to_string()))?;
+
+        self.select(&id)
+    }
+
+    /// Select the node with `id` in the current node list, without recording the jump.
+    pub fn select(&mut self, id: &str) -> DotViewerResult<()> {
         let idx = (self.current)
             .find(id.to_string())
             .ok_or(DotViewerError::ViewerError(format!("no such node {id:?}")))?;
@@ -149,7 +302,8 @@ impl View {
             return Err(DotViewerError::ViewerError(format!("no match for keyword {key}")));
         }
 
-        Self::new(format!("{} - {}", self.title, self.key), graph)
+        let step = Step::Filter { smode: self.smode.clone(), key: self.key.clone() };
+        self.derive(format!("{} - {}", self.title, self.key), graph, step)
     }
 
     /// Extract a subgraph from the view.
@@ -159,6 +313,13 @@ impl View {
             .selected()
             .ok_or(DotViewerError::ViewerError("no subgraph selected".to_string()))?;
 
+        self.subgraph_of(&key)
+    }
+
+    /// Extract a subgraph with id `key` from the view.
+    /// Returns `Ok` with a new `View` if the subgraph id is valid.
+    pub fn subgraph_of(&self, key: &str) -> DotViewerResult<View> {
+        let key = key.to_string();
         let subgraph =
             self.graph.subgraph(&key).map_err(|e| DotViewerError::ViewerError(e.to_string()))?;
 
@@ -167,7 +328,7 @@ impl View {
         }
 
         let title = &self.title;
-        Self::new(format!("{title} - {key}"), subgraph)
+        self.derive(format!("{title} - {key}"), subgraph, Step::Subgraph { id: key.clone() })
     }
 
     /// Get neighbors graph from the selected id in the view.
@@ -181,7 +342,167 @@ impl View {
         }
 
         let title = &self.title;
-        Self::new(format!("{title} - neighbors-{id}-{depth}"), graph)
+        let step = Step::Neighbors { id: id.clone(), depth };
+        self.derive(format!("{title} - neighbors-{id}-{depth}"), graph, step)
+    }
+
+    /// Get a graph of the ancestors (or descendants) of `seeds` in the view, following edges in
+    /// `direction` up to `depth` hops, or the full transitive cone if `depth` is not given.
+    /// Returns `Ok` with a new `View` if all seeds are in the view.
+    pub fn cone(
+        &self,
+        seeds: &[String],
+        depth: Option<usize>,
+        direction: Direction,
+    ) -> DotViewerResult<View> {
+        for id in seeds {
+            if self.graph.search_node(id).is_none() {
+                return Err(DotViewerError::ViewerError(format!("no such node {id:?}")));
+            }
+        }
+
+        let node_ids = reachable(&self.graph, seeds, depth, direction)?;
+        let node_ids: Vec<&String> = node_ids.iter().collect();
+        let graph = self.graph.filter(&node_ids);
+
+        let title = &self.title;
+        let name = match direction {
+            Direction::Forward => "descendants",
+            Direction::Backward => "ancestors",
+        };
+        let seed = match seeds {
+            [id] => id.clone(),
+            _ => format!("{}-nodes", seeds.len()),
+        };
+        let depth_name = depth.map_or("all".to_string(), |depth| depth.to_string());
+        let step = Step::Cone { seeds: seeds.to_vec(), depth, direction };
+        self.derive(format!("{title} - {name}-{seed}-{depth_name}"), graph, step)
+    }
+
+    /// Extract the strongly connected component selected in the cycle list.
+    /// Returns `Ok` with a new `View` if a cycle is selected.
+    pub fn cycle(&self) -> DotViewerResult<View> {
+        let cycle = (self.cycles)
+            .selected()
+            .ok_or(DotViewerError::ViewerError("no cycle selected".to_string()))?;
+
+        self.cycle_of(&cycle.nodes[0])
+    }
+
+    /// Extract the strongly connected component that the node `id` sits on as a cycle.
+    /// Returns `Ok` with a new `View` if the node is on a cycle.
+    pub fn cycle_of(&self, id: &str) -> DotViewerResult<View> {
+        let cycle = (self.cycles.items.iter())
+            .find(|cycle| cycle.nodes.iter().any(|node| node == id))
+            .ok_or(DotViewerError::ViewerError(format!("no cycle through {id:?}")))?;
+
+        let node_ids: Vec<&String> = cycle.nodes.iter().collect();
+        let graph = self.graph.filter(&node_ids);
+
+        let title = &self.title;
+        let step = Step::Cycle { id: id.to_string() };
+        self.derive(format!("{title} - cycle-{id}"), graph, step)
+    }
+
+    /// Analyze the longest weighted path through the view, with weights from `attr` if given.
+    /// Returns `Ok` with a new `View` of the same graph, highlighting the critical path.
+    pub fn critical(&self, attr: Option<&str>) -> DotViewerResult<View> {
+        if !self.cycles.items.is_empty() {
+            return Err(DotViewerError::ViewerError(
+                "critical path needs an acyclic graph".to_string(),
+            ));
+        }
+
+        let critical = Critical::new(&self.graph, &self.current.items, attr)?;
+
+        let node_ids: Vec<&String> = self.current.items.iter().collect();
+        let graph = self.graph.filter(&node_ids);
+
+        let title = &self.title;
+        let name = attr.unwrap_or("nodes");
+        let step = Step::Critical { attr: attr.map(String::from) };
+        let mut view = self.derive(format!("{title} - critical-{name}"), graph, step)?;
+
+        // start on the critical path
+        let _ = view.select(&critical.path[0]);
+        view.critical = Some(critical);
+
+        Ok(view)
+    }
+
+    /// Get a graph of nodes on any path between `from` and `to` in the view,
+    /// or only those on a shortest path if `shortest` is set.
+    /// Returns `Ok` with a new `View` if there is a path in either direction.
+    pub fn path(&self, from: &str, to: &str, shortest: bool) -> DotViewerResult<View> {
+        for id in [from, to] {
+            if self.current.find(id.to_string()).is_none() {
+                return Err(DotViewerError::ViewerError(format!("no such node {id:?}")));
+            }
+        }
+
+        let from = from.to_string();
+        let to = to.to_string();
+
+        let mut node_ids = between(&self.graph, &from, &to, shortest)?;
+        if node_ids.is_empty() {
+            node_ids = between(&self.graph, &to, &from, shortest)?;
+        }
+
+        if node_ids.is_empty() {
+            return Err(DotViewerError::ViewerError(format!("no path between {from} and {to}")));
+        }
+
+        let node_ids: Vec<&String> = node_ids.iter().collect();
+        let graph = self.graph.filter(&node_ids);
+
+        let title = &self.title;
+        let name = if shortest { "shortest-path" } else { "path" };
+        let step = Step::Path { from: from.clone(), to: to.clone(), shortest };
+        self.derive(format!("{title} - {name}-{from}-{to}"), graph, step)
+    }
+
+    /// Combine the nodes of the view with those of `other` by the set operation `op`,
+    /// taking the edges between them from `root`, the view that both are derived from.
+    /// Returns `Ok` with a new `View` if the combination is not empty.
+    pub fn combine(&self, other: &View, op: SetOp, root: &View) -> DotViewerResult<View> {
+        let lhs: HashSet<&String> = self.current.items.iter().collect();
+        let rhs: HashSet<&String> = other.current.items.iter().collect();
+
+        let node_ids: Vec<&String> = match op {
+            SetOp::Union => lhs.union(&rhs).copied().collect(),
+            SetOp::Intersect => lhs.intersection(&rhs).copied().collect(),
+            SetOp::Diff => lhs.difference(&rhs).copied().collect(),
+        };
+
+        if node_ids.is_empty() {
+            let name = op.name();
+            return Err(DotViewerError::ViewerError(format!("empty {name} of views")));
+        }
+
+        let graph = root.graph.filter(&node_ids);
+
+        let mut view = Self::new(format!("{} {} {}", self.title, op.symbol(), other.title), graph)?;
+        view.recipe =
+            vec![Step::Combine { op, lhs: self.recipe.clone(), rhs: other.recipe.clone() }];
+
+        Ok(view)
+    }
+
+    /// Constructs a new `View` derived from this view by `step`.
+    fn derive(&self, title: String, graph: Graph, step: Step) -> DotViewerResult<View> {
+        let mut view = Self::new(title, graph)?;
+
+        view.recipe = self.recipe.clone();
+        view.recipe.push(step);
+
+        Ok(view)
+    }
+
+    /// Restore the attributes mode and jumps of `old`, which this view is rebuilt from,
+    /// on top of what `resume` restores from its snapshot.
+    pub fn restore(&mut self, mut old: View) {
+        self.attrs = old.attrs;
+        self.jumps = mem::take(&mut old.jumps);
     }
 
     /// Autocomplete a given keyword, coming from `tab` keybinding.
@@ -207,7 +528,10 @@ impl View {
     }
 
     /// Update matches based on the given matching function `match` with input `key`.
-    fn update_matches(&mut self, matcher: Matcher, key: &str) {
+    fn update_matches<M>(&mut self, matcher: M, key: &str)
+    where
+        M: Fn(&str, &str, &Graph) -> Option<Vec<usize>> + Sync,
+    {
         let matches: Vec<(usize, Vec<usize>)> = (self.current.items.par_iter())
             .enumerate()
             .filter_map(|(idx, id)| matcher(id, key, &self.graph).map(|highlight| (idx, highlight)))
@@ -220,15 +544,42 @@ impl View {
     /// Update matches in fuzzy search mode.
     /// Fuzzy matcher matches input against node ids.
     pub fn update_fuzzy(&mut self, key: &str) {
+        self.smode = SearchMode::Fuzzy;
         self.update_matches(match_fuzzy, key);
     }
 
     /// Update matches in regex search mode.
     /// Regex matcher matches input against node represented in raw dot format string.
     pub fn update_regex(&mut self, key: &str) {
+        self.smode = SearchMode::Regex;
         self.update_matches(match_regex, key);
     }
 
+    /// Update matches in query search mode.
+    /// Query matcher evaluates predicates on node attributes and degrees,
+    /// and an invalid query matches no nodes.
+    pub fn update_query(&mut self, key: &str) {
+        self.smode = SearchMode::Query;
+
+        match Query::new(key, &self.graph) {
+            Ok(query) => self.update_matches(|id, _, graph| match_query(&query, id, graph), key),
+            Err(_) => {
+                self.key = key.to_string();
+                self.matches = List::from_iter(Vec::new());
+            }
+        }
+    }
+
+    /// Update matches and trie in search mode `smode`.
+    pub fn update_search(&mut self, smode: &SearchMode, key: &str) {
+        match smode {
+            SearchMode::Fuzzy => self.update_fuzzy(key),
+            SearchMode::Regex => self.update_regex(key),
+            SearchMode::Query => self.update_query(key),
+        }
+        self.update_trie();
+    }
+
     /// Update trie based on the current matches.
     pub fn update_trie(&mut self) {
         let nodes = self.matches.items.iter().map(|(idx, _)| self.current.items[*idx].clone());
@@ -239,6 +590,16 @@ impl View {
         self.current.selected().expect("there is always a current id selected in a view")
     }
 
+    /// Endpoint ids of the edges between the current node and the selected prev or next node,
+    /// when the prevs or nexts list is focused.
+    pub fn selected_edge(&self) -> Option<(String, String)> {
+        match self.focus {
+            Focus::Current => None,
+            Focus::Prev => self.prevs.selected().map(|prev| (prev, self.current_id())),
+            Focus::Next => self.nexts.selected().map(|next| (self.current_id(), next)),
+        }
+    }
+
     pub fn matched_id(&self) -> Option<String> {
         self.matches.selected().map(|(idx, _)| self.current.items[idx].clone())
     }
@@ -260,6 +621,27 @@ impl View {
     }
 }
 
+/// Collect nodes on paths from `from` to `to`, which is empty if there is no such path.
+fn between(
+    graph: &Graph,
+    from: &String,
+    to: &String,
+    shortest: bool,
+) -> DotViewerResult<Vec<String>> {
+    if shortest {
+        return Ok(shortest_path(graph, from, to)?.unwrap_or_default());
+    }
+
+    let descendants = reachable(graph, &[from.clone()], None, Direction::Forward)?;
+    if !descendants.contains(to) {
+        return Ok(Vec::new());
+    }
+
+    let ancestors = reachable(graph, &[to.clone()], None, Direction::Backward)?;
+
+    Ok(descendants.intersection(&ancestors).cloned().collect())
+}
+
 fn match_fuzzy(id: &str, key: &str, _graph: &Graph) -> Option<Vec<usize>> {
     let matcher = SkimMatcherV2::default();
 
@@ -280,3 +662,8 @@ fn match_regex(id: &str, key: &str, graph: &Graph) -> Option<Vec<usize>> {
         None
     }
 }
+
+fn match_query(query: &Query, id: &str, graph: &Graph) -> Option<Vec<usize>> {
+    let highlight: Vec<usize> = (0..id.chars().count()).collect();
+    query.eval(id, graph).then_some(highlight)
+}